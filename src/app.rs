@@ -3,13 +3,32 @@ use leptos::html::{Div, Input};
 use leptos::logging::log;
 use leptos::prelude::*;
 use leptos_meta::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::Arc;
 use leptos_router::{components::*, path};
 use leptos_use::core::IntoElementMaybeSignal;
-use leptos_use::{sync_signal_with_options, use_color_mode_with_options, use_event_listener, use_event_listener_with_options, use_mutation_observer_with_options, use_preferred_dark, use_window_focus, ColorMode, SyncSignalOptions, UseColorModeOptions, UseColorModeReturn, UseMutationObserverOptions};
-use crate::model::error::ArcZhongCharError;
-use crate::model::radical::Radical;
-use leptos_router::hooks::use_location;
-use leptos::ev::{blur, focus, MouseEvent};
+use leptos_use::{sync_signal_with_options, use_color_mode_with_options, use_event_listener, use_event_listener_with_options, use_interval_fn, use_mutation_observer_with_options, use_preferred_dark, use_window_focus, ColorMode, SyncSignalOptions, UseColorModeOptions, UseColorModeReturn, UseMutationObserverOptions};
+use crate::helpers::{character_href, page_href, radical_href};
+use crate::datasets::{DatasetProgress, FetchPolicy};
+use crate::data_export::{AnkiExport, BackupRestore};
+use crate::flashcards::FlashcardSession;
+use crate::lookup::{radical_index_view, residual_index_view, residual_strokes_view};
+use crate::quiz::QuizRunner;
+use crate::storage::{load_character_graphics, load_dataset, read_card_states, read_review_history};
+use crate::component_strokes::{role_text_class, ComponentStrokes};
+use crate::stroke_order::StrokeOrderSection;
+use zhongchar_core::character::{Character, ComponentPath, Etymology};
+use zhongchar_core::error::{ArcZhongCharError, ZhongCharError, ZhongCharResult};
+use zhongchar_core::graphics::CharacterGraphics;
+use zhongchar_core::ids::{Decomposition, UNKNOWN_COMPONENT};
+use zhongchar_core::radical::{Radical, Romanized};
+use zhongchar_core::script::ScriptMap;
+use zhongchar_core::progress::StudyProgress;
+use zhongchar_core::search::{RadicalFilter, RadicalSort, SortColumn, SortDirection};
+use crate::service_worker::{apply_update, UpdateWaiting};
+use leptos_router::hooks::{use_location, use_navigate, use_params_map, use_query_map};
+use leptos::ev::{blur, focus, MouseEvent, SubmitEvent};
 use web_sys::PointerEvent;
 use leptos::wasm_bindgen::JsCast;
 
@@ -164,10 +183,34 @@ fn Nav() -> impl IntoView {
             <div class="navbar-center">
                 <a href=move || format!("{}{}", base_url, "radicals")
                     class="btn btn-ghost hover:bg-transparent"
-                    class=("underline", move || pathname.get() == "/radicals".to_string())
+                    class=("underline", move || pathname.get().starts_with("/radicals"))
                 >
                     Radicals
                 </a>
+                <a href=move || format!("{}{}", base_url, "lookup")
+                    class="btn btn-ghost hover:bg-transparent"
+                    class=("underline", move || pathname.get().starts_with("/lookup"))
+                >
+                    Lookup
+                </a>
+                <a href=move || format!("{}{}", base_url, "flashcards")
+                    class="btn btn-ghost hover:bg-transparent"
+                    class=("underline", move || pathname.get().starts_with("/flashcards"))
+                >
+                    Flashcards
+                </a>
+                <a href=move || format!("{}{}", base_url, "quiz")
+                    class="btn btn-ghost hover:bg-transparent"
+                    class=("underline", move || pathname.get().starts_with("/quiz"))
+                >
+                    Quiz
+                </a>
+                <a href=move || format!("{}{}", base_url, "data")
+                    class="btn btn-ghost hover:bg-transparent"
+                    class=("underline", move || pathname.get().starts_with("/data"))
+                >
+                    Data
+                </a>
             </div>
             <div class="navbar-end">
                 <div class="dropdown dropdown-end">
@@ -268,52 +311,279 @@ pub fn App() -> impl IntoView {
             <Routes fallback=|| "Page not found.">
                 <Route path=path!("/") view=Home/>
                 <Route path=path!("/radicals") view=Radicals/>
+                <Route path=path!("/radicals/:number") view=RadicalDetail/>
+                <Route path=path!("/char/:c") view=CharacterDetail/>
+                <Route path=path!("/lookup") view=Lookup/>
+                <Route path=path!("/lookup/:number") view=Lookup/>
+                <Route path=path!("/flashcards") view=Flashcards/>
+                <Route path=path!("/quiz") view=Quiz/>
+                <Route path=path!("/data") view=Data/>
             </Routes>
         </Router>
+        <UpdatePrompt/>
+    }
+}
+
+#[component]
+fn UpdatePrompt() -> impl IntoView {
+    let update_waiting = use_context::<UpdateWaiting>()
+        .map(|UpdateWaiting(waiting)| waiting)
+        .unwrap_or_else(|| RwSignal::new(false));
+    view! {
+        <Show when=move || update_waiting.get()>
+            <div class="toast toast-end z-50">
+                <div role="alert" class="alert alert-info shadow-lg">
+                    <span>"A new version of 中 Char is available."</span>
+                    <button class="btn btn-sm" on:click=move |_| update_waiting.set(false)>"Later"</button>
+                    <button class="btn btn-sm btn-primary" on:click=move |_| apply_update()>"Update"</button>
+                </div>
+            </div>
+        </Show>
     }
 }
 
 #[component]
 fn Home() -> impl IntoView {
+    let study_history = LocalResource::new(|| async {
+        let states = read_card_states().await?;
+        let history = read_review_history().await?;
+        Ok::<_, ArcZhongCharError>((states, history))
+    });
+    let navigate = use_navigate();
+    let search_query = RwSignal::new(String::new());
+    let search = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        let query = String::from(js_sys::encode_uri_component(search_query.get_untracked().trim()));
+        navigate(&page_href(&format!("radicals?q={}", query)), Default::default());
+    };
     view! {
         <main>
             <Title text="中 Char"/>
             <Nav/>
             <div class="w-full h-dvh absolute top-0 flex flex-col">
                 <div class="w-full h-16 flex-none"></div>
-                hello world
+                <div class="w-full flex-grow overflow-y-auto p-4 flex flex-col gap-6">
+                    <Suspense fallback=|| view! { <div class="skeleton w-full max-w-4xl h-32"></div> }>
+                        <ErrorBoundary fallback=|errors| view! {
+                            <p class="opacity-60" title=move || errors.get().into_iter().map(|(_, e)| e.to_string()).collect::<Vec<_>>().join("\n")>
+                                "Study history is unavailable."
+                            </p>
+                        }>
+                            {move || Suspend::new(async move {
+                                study_history.await.map(|(states, history)| {
+                                    let now = js_sys::Date::now();
+                                    let local_offset_ms = -js_sys::Date::new_0().get_timezone_offset() * 60_000.0;
+                                    let progress = StudyProgress::compute(&states, &history, now, local_offset_ms, TREND_DAYS);
+                                    dashboard_view(progress)
+                                })
+                            })}
+                        </ErrorBoundary>
+                    </Suspense>
+                    <div class="flex flex-wrap items-center gap-2">
+                        <a class="btn" href=page_href("quiz")>"Take a quiz"</a>
+                        <form class="join" on:submit=search>
+                            <input class="input join-item" type="search" placeholder="Search radicals" bind:value=search_query/>
+                            <button class="btn join-item" type="submit">"Search"</button>
+                        </form>
+                    </div>
+                </div>
             </div>
         </main>
     }
 }
 
+/// Days of accuracy shown on the dashboard.
+const TREND_DAYS: usize = 14;
+
+fn dashboard_view(progress: StudyProgress) -> impl IntoView {
+    let (study_label, study_class) = match progress.due_today {
+        0 if progress.reviews == 0 => ("Start studying".to_string(), "btn btn-primary"),
+        0 => ("Learn new cards".to_string(), "btn"),
+        due => (format!("Review {} due {}", due, if due == 1 { "card" } else { "cards" }), "btn btn-primary"),
+    };
+    let today = progress.daily.last().map_or(0, |day| day.day);
+    let trend = progress.daily.iter().map(|day| {
+        let label = match today - day.day {
+            0 => "Today".to_string(),
+            1 => "Yesterday".to_string(),
+            n => format!("{} days ago", n),
+        };
+        let title = if day.total == 0 {
+            format!("{}: no reviews", label)
+        } else {
+            format!("{}: {} of {} correct", label, day.correct, day.total)
+        };
+        // Days without reviews keep a sliver so the timeline stays readable.
+        let height = if day.total == 0 { 2.0 } else { (day.accuracy() * 100.0).max(4.0) };
+        let class = if day.total == 0 { "w-3 rounded-t bg-base-300" } else { "w-3 rounded-t bg-primary" };
+        view! {
+            <div class="h-full flex items-end" title=title>
+                <div class=class style=format!("height: {}%", height)></div>
+            </div>
+        }
+    }).collect::<Vec<_>>();
+
+    view! {
+        <div class="flex flex-col gap-6 max-w-4xl">
+            <div class="stats stats-vertical sm:stats-horizontal shadow">
+                <div class="stat">
+                    <div class="stat-title">"Due today"</div>
+                    <div class="stat-value">{progress.due_today}</div>
+                    <div class="stat-desc">{progress.reviews}" reviews so far"</div>
+                </div>
+                <div class="stat">
+                    <div class="stat-title">"Radicals learned"</div>
+                    <div class="stat-value">{progress.radicals_learned}" / "{Radical::COUNT}</div>
+                    <div class="stat-desc">
+                        <progress class="progress progress-primary w-32"
+                            value=progress.radicals_learned.to_string()
+                            max=Radical::COUNT.to_string()
+                        ></progress>
+                        <span class="ml-2">{progress.radicals_remaining()}" to go"</span>
+                    </div>
+                </div>
+                <div class="stat">
+                    <div class="stat-title">"Review streak"</div>
+                    <div class="stat-value">{progress.current_streak}" "{if progress.current_streak == 1 { "day" } else { "days" }}</div>
+                    <div class="stat-desc">"Longest "{progress.longest_streak}</div>
+                </div>
+                <div class="stat">
+                    <div class="stat-title">"Accuracy"</div>
+                    <div class="stat-value">
+                        {progress.accuracy().map_or("–".to_string(), |accuracy| format!("{}%", (accuracy * 100.0).round()))}
+                    </div>
+                    <div class="stat-desc">{progress.correct}" answers recalled"</div>
+                </div>
+            </div>
+            <section class="flex flex-col gap-2">
+                <h2 class="text-lg font-bold">"Accuracy, last "{TREND_DAYS}" days"</h2>
+                <div class="flex gap-1 h-24 w-fit p-2 bg-base-200 rounded-box">{trend}</div>
+            </section>
+            <a class=study_class href=page_href("flashcards")>{study_label}</a>
+        </div>
+    }
+}
+
 #[component]
 fn Radicals() -> impl IntoView {
-    let radicals_fetched = LocalResource::new(move || async move {
-        Radical::fetch_radicals()
-            .await
-            .map_err(|e| ArcZhongCharError::from(e))
-    });
+    let radicals_fetched = use_radicals();
+    // Pre-filled when arriving from the dashboard's search box.
+    let search_query = RwSignal::new(use_query_map().read_untracked().get("q").unwrap_or_default());
+    let radical_sort = RwSignal::new(RadicalSort::default());
+    let radical_filter = RwSignal::new(RadicalFilter::default());
     view! {
         <main>
             <Title text="中 Char"/>
             <Nav/>
             <div class="w-full h-dvh absolute top-0 flex flex-col">
                 <div class="w-full h-16 flex-none"></div>
+                <div class="w-full flex-none p-2">
+                    <label class="input w-full max-w-md">
+                        <svg xmlns="http://www.w3.org/2000/svg" fill="none" viewBox="0 0 24 24" stroke-width="1.5" stroke="currentColor" class="size-5 opacity-50">
+                            <path stroke-linecap="round" stroke-linejoin="round" d="m21 21-5.197-5.197m0 0A7.5 7.5 0 1 0 5.196 5.196a7.5 7.5 0 0 0 10.607 10.607Z" />
+                        </svg>
+                        <input
+                            type="search"
+                            placeholder="Search meaning, pinyin, Hán-Việt, kana, hangul or glyph"
+                            bind:value=search_query
+                        />
+                    </label>
+                    <div class="flex flex-wrap items-center gap-x-6 gap-y-2 pt-2 text-sm">
+                        <label class="flex items-center gap-2">
+                            "Strokes"
+                            <input
+                                type="number"
+                                class="input input-sm w-16"
+                                min=RadicalFilter::STROKE_COUNT_RANGE.0
+                                max=RadicalFilter::STROKE_COUNT_RANGE.1
+                                prop:value=move || radical_filter.get().min_stroke_count
+                                on:input=move |ev| {
+                                    let Ok(min_stroke_count) = event_target_value(&ev).parse() else { return };
+                                    radical_filter.update(|filter| filter.min_stroke_count = min_stroke_count);
+                                }
+                            />
+                            "to"
+                            <input
+                                type="number"
+                                class="input input-sm w-16"
+                                min=RadicalFilter::STROKE_COUNT_RANGE.0
+                                max=RadicalFilter::STROKE_COUNT_RANGE.1
+                                prop:value=move || radical_filter.get().max_stroke_count
+                                on:input=move |ev| {
+                                    let Ok(max_stroke_count) = event_target_value(&ev).parse() else { return };
+                                    radical_filter.update(|filter| filter.max_stroke_count = max_stroke_count);
+                                }
+                            />
+                        </label>
+                        <label class="flex items-center gap-2">
+                            "Min frequency"
+                            <input
+                                type="number"
+                                class="input input-sm w-20"
+                                min="0"
+                                prop:value=move || radical_filter.get().min_frequency
+                                on:input=move |ev| {
+                                    let min_frequency = event_target_value(&ev).parse().unwrap_or(0);
+                                    radical_filter.update(|filter| filter.min_frequency = min_frequency);
+                                }
+                            />
+                        </label>
+                        <label class="flex items-center gap-2">
+                            <input
+                                type="checkbox"
+                                class="toggle toggle-sm"
+                                prop:checked=move || radical_filter.get().has_simplified
+                                on:change=move |ev| {
+                                    let has_simplified = event_target_checked(&ev);
+                                    radical_filter.update(|filter| filter.has_simplified = has_simplified);
+                                }
+                            />
+                            "Has simplified form"
+                        </label>
+                        <label class="flex items-center gap-2">
+                            <input
+                                type="checkbox"
+                                class="toggle toggle-sm"
+                                prop:checked=move || radical_filter.get().has_colloquial_term
+                                on:change=move |ev| {
+                                    let has_colloquial_term = event_target_checked(&ev);
+                                    radical_filter.update(|filter| filter.has_colloquial_term = has_colloquial_term);
+                                }
+                            />
+                            "Has colloquial term"
+                        </label>
+                        <span class="opacity-70">
+                            <Suspense fallback=|| view! { <LoadingStatus/> }>
+                                {move || {
+                                    let query = search_query.get();
+                                    let filter = radical_filter.get();
+                                    Suspend::new(async move {
+                                        radicals_fetched.await.ok().map(|radicals| {
+                                            let matching = radicals.iter()
+                                                .filter(|radical| filter.matches(radical) && radical.matches_search(&query))
+                                                .count();
+                                            format!("{} of {} radicals", matching, radicals.len())
+                                        })
+                                    })
+                                }}
+                            </Suspense>
+                        </span>
+                    </div>
+                </div>
                 <div class="w-full flex-grow overflow-x-auto overflow-y-auto">
                 <table class="table table-zebra table-pin-rows w-full">
                     <thead>
                         <tr>
-                            <th>"Number"</th>
+                            <SortableHeader label="Number" column=SortColumn::Number sort=radical_sort/>
                             <th>"Radical Forms"</th>
-                            <th>"Stroke Count"</th>
-                            <th>"Meaning"</th>
+                            <SortableHeader label="Stroke Count" column=SortColumn::StrokeCount sort=radical_sort/>
+                            <SortableHeader label="Meaning" column=SortColumn::Meaning sort=radical_sort/>
                             <th>"Colloquial Term"</th>
-                            <th>"Pinyin"</th>
+                            <SortableHeader label="Pinyin" column=SortColumn::Pinyin sort=radical_sort/>
                             <th>"Han Viet"</th>
                             <th>"Hiragana/Romaji"</th>
                             <th>"Hangul/Romaja"</th>
-                            <th>"Frequency"</th>
+                            <SortableHeader label="Frequency" column=SortColumn::Frequency sort=radical_sort/>
                             <th>"Simplified"</th>
                             <th>"Examples"</th>
                         </tr>
@@ -330,47 +600,49 @@ fn Radicals() -> impl IntoView {
                             })
                             .collect::<Vec<_>>()
                         }>
-                            <ErrorBoundary fallback=|errors| view! {
-                                <div class="error">
-                                    <p>"Not a number! Errors: "</p>
-                                    <ul>
-                                        {move || errors.get()
-                                            .into_iter()
-                                            .map(|(_, e)| view! { <li>{e.to_string()}</li>})
-                                            .collect::<Vec<_>>()
-                                        }
-                                    </ul>
-                                </div>
+                            <ErrorBoundary fallback=move |errors| view! {
+                                <tr>
+                                    <td colspan="12">
+                                        {load_error_view("the radicals", errors, move || radicals_fetched.refetch())}
+                                    </td>
+                                </tr>
                                 }
                             >
-                                { move || Suspend::new ( async move {
-                                    radicals_fetched.await.map(|radicals| { // Changed this line
-                                        view! {
-                                            <> // Added Fragment here
-                                                {
-                                                    radicals.into_iter().map(|radical| {
-                                                        view! {
-                                                            <tr>
-                                                                <td>{radical.number}</td>
-                                                                <td>{radical.radical_forms}</td>
-                                                                <td>{radical.stroke_count}</td>
-                                                                <td>{radical.meaning}</td>
-                                                                <td>{radical.colloquial_term}</td>
-                                                                <td>{radical.pinyin}</td>
-                                                                <td>{radical.han_viet}</td>
-                                                                <td>{radical.hiragana_romaji}</td>
-                                                                <td>{radical.hangul_romaja}</td>
-                                                                <td>{radical.frequency}</td>
-                                                                <td>{radical.simplified}</td>
-                                                                <td>{radical.examples}</td>
-                                                            </tr>
-                                                        }
-                                                    }).collect::<Vec<_>>()
-                                                }
-                                            </> // Close Fragment here
-                                        }
+                                { move || {
+                                    let query = search_query.get();
+                                    let sort = radical_sort.get();
+                                    let filter = radical_filter.get();
+                                    Suspend::new ( async move {
+                                        radicals_fetched.await.map(|mut radicals| { // Changed this line
+                                            radicals.retain(|radical| filter.matches(radical) && radical.matches_search(&query));
+                                            sort.sort(&mut radicals);
+                                            view! {
+                                                <> // Added Fragment here
+                                                    {
+                                                        radicals.into_iter().map(|radical| {
+                                                            view! {
+                                                                <tr>
+                                                                    <td><a class="link" href=radical_href(radical.number)>{radical.number}</a></td>
+                                                                    <td><a href=radical_href(radical.number)>{radical.radical_forms.to_string()}</a></td>
+                                                                    <td>{radical.stroke_count}</td>
+                                                                    <td>{radical.meaning}</td>
+                                                                    <td>{radical.colloquial_term}</td>
+                                                                    <td>{radical.pinyin}</td>
+                                                                    <td>{radical.han_viet}</td>
+                                                                    <td>{radical.hiragana_romaji.to_string()}</td>
+                                                                    <td>{radical.hangul_romaja.to_string()}</td>
+                                                                    <td>{radical.frequency}</td>
+                                                                    <td>{radical.simplified.map(|simplified| simplified.to_string())}</td>
+                                                                    <td>{radical.examples_raw}</td>
+                                                                </tr>
+                                                            }
+                                                        }).collect::<Vec<_>>()
+                                                    }
+                                                </> // Close Fragment here
+                                            }
+                                        })
                                     })
-                                })}
+                                }}
                             </ErrorBoundary>
                         </Suspense>
                     </tbody>
@@ -383,6 +655,690 @@ fn Radicals() -> impl IntoView {
 }
 
 
+#[component]
+fn SortableHeader(label: &'static str, column: SortColumn, sort: RwSignal<RadicalSort>) -> impl IntoView {
+    let active_direction = move || {
+        let current = sort.get();
+        (current.column == column).then_some(current.direction)
+    };
+    view! {
+        <th aria-sort=move || match active_direction() {
+            Some(SortDirection::Ascending) => "ascending",
+            Some(SortDirection::Descending) => "descending",
+            None => "none",
+        }>
+            <button
+                class="flex items-center gap-1 cursor-pointer"
+                on:click=move |_| sort.update(|current| *current = current.toggled(column))
+            >
+                {label}
+                <span class="w-3">
+                    {move || match active_direction() {
+                        Some(SortDirection::Ascending) => "▲",
+                        Some(SortDirection::Descending) => "▼",
+                        None => "",
+                    }}
+                </span>
+            </button>
+        </th>
+    }
+}
+
+#[component]
+fn RadicalDetail() -> impl IntoView {
+    let params = use_params_map();
+    let requested_number = move || params.read().get("number").and_then(|n| n.parse::<i32>().ok());
+    let radicals_fetched = use_radicals();
+    let characters_fetched = use_characters();
+    let script_map_built = use_script_map(radicals_fetched, characters_fetched);
+    view! {
+        <main>
+            <Title text="中 Char"/>
+            <Nav/>
+            <div class="w-full h-dvh absolute top-0 flex flex-col">
+                <div class="w-full h-16 flex-none"></div>
+                <div class="w-full flex-grow overflow-y-auto p-4">
+                    <Suspense fallback=move || view! {
+                        <div class="flex flex-col gap-2">
+                            <div class="skeleton w-40 h-40"></div>
+                            <span class="opacity-70"><LoadingStatus/></span>
+                        </div>
+                    }>
+                        <ErrorBoundary fallback=move |errors| load_error_view("this radical", errors, move || {
+                            radicals_fetched.refetch();
+                            characters_fetched.refetch();
+                        })>
+                            { move || {
+                                let requested = requested_number();
+                                Suspend::new(async move {
+                                    let radicals = radicals_fetched.await?;
+                                    let characters = characters_fetched.await?;
+                                    let script_map = script_map_built.await?;
+                                    let found = requested.and_then(|requested| {
+                                        radicals.into_iter().find(|radical| radical.number == requested)
+                                    });
+                                    Ok::<_, ArcZhongCharError>(match found {
+                                        Some(radical) => radical_detail_view(radical, &characters, &script_map).into_any(),
+                                        None => view! {
+                                            <p>"There is no radical number "{requested}"."</p>
+                                        }.into_any(),
+                                    })
+                                })
+                            }}
+                        </ErrorBoundary>
+                    </Suspense>
+                </div>
+            </div>
+        </main>
+    }
+}
+
+fn radical_detail_view(radical: Radical, dictionary: &[Character], script_map: &ScriptMap) -> impl IntoView {
+    let characters_by_residual_strokes = radical.characters_by_residual_strokes(dictionary);
+    let character_count: usize = characters_by_residual_strokes.values().map(|characters| characters.len()).sum();
+    let primary_form = radical.radical_forms.primary.to_string();
+    let variant_forms = radical.radical_forms.variants.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(" ");
+
+    view! {
+        <div class="flex flex-col gap-6">
+            <div class="flex items-end gap-6">
+                <span class="text-9xl leading-none">{primary_form}</span>
+                <div class="flex flex-col gap-1">
+                    <span class="text-2xl">{variant_forms}</span>
+                    <span class="opacity-60">"Radical "{radical.number}</span>
+                    {script_counterparts_view(radical.radical_forms.primary, script_map)}
+                </div>
+            </div>
+            <dl class="grid grid-cols-[auto_1fr] gap-x-4 gap-y-1 max-w-2xl">
+                <dt class="font-semibold">"Stroke Count"</dt><dd>{radical.stroke_count}</dd>
+                <dt class="font-semibold">"Meaning"</dt><dd>{radical.meaning}</dd>
+                <dt class="font-semibold">"Colloquial Term"</dt><dd>{radical.colloquial_term}</dd>
+                <dt class="font-semibold">"Pinyin"</dt><dd>{radical.pinyin}</dd>
+                <dt class="font-semibold">"Han Viet"</dt><dd>{radical.han_viet}</dd>
+                <dt class="font-semibold">"Hiragana/Romaji"</dt><dd>{romanized_view(radical.hiragana_romaji)}</dd>
+                <dt class="font-semibold">"Hangul/Romaja"</dt><dd>{romanized_view(radical.hangul_romaja)}</dd>
+                <dt class="font-semibold">"Frequency"</dt><dd>{radical.frequency}</dd>
+                <dt class="font-semibold">"Simplified"</dt><dd>{radical.simplified.map(|simplified| simplified.to_string())}</dd>
+                <dt class="font-semibold">"Examples"</dt>
+                <dd class="flex gap-2">
+                    {radical.examples.into_iter().map(|example| view! {
+                        <a class="link link-hover" href=character_href(example)>{example.to_string()}</a>
+                    }).collect::<Vec<_>>()}
+                </dd>
+            </dl>
+            <section class="flex flex-col gap-2">
+                <h2 class="text-lg font-bold">{character_count}" characters"</h2>
+                {residual_strokes_view(characters_by_residual_strokes)}
+            </section>
+        </div>
+    }
+}
+
+/// Links to the simplified form of a traditional character, or to the
+/// traditional forms of a simplified one.
+fn script_counterparts_view(character: char, script_map: &ScriptMap) -> impl IntoView {
+    let simplified = script_map.to_simplified(character).map(|simplified| view! {
+        <span>
+            "Simplified "
+            <a class="link" href=character_href(simplified)>{simplified.to_string()}</a>
+        </span>
+    });
+    let traditional = script_map.to_traditional(character).to_vec();
+    let traditional = (!traditional.is_empty()).then(|| view! {
+        <span>
+            "Traditional "
+            {traditional.into_iter().map(|form| view! {
+                <a class="link mr-1" href=character_href(form)>{form.to_string()}</a>
+            }).collect::<Vec<_>>()}
+        </span>
+    });
+    view! { {simplified}{traditional} }
+}
+
+fn romanized_view(name: Romanized) -> impl IntoView {
+    view! {
+        <span>{name.native}</span>
+        <span class="opacity-60 ml-2">{name.romanized}</span>
+    }
+}
+
+/// Looking a character up by radical and remaining strokes, for characters
+/// the learner can't pronounce.
+#[component]
+fn Lookup() -> impl IntoView {
+    let params = use_params_map();
+    let selected = Memo::new(move |_| params.read().get("number").and_then(|n| n.parse::<i32>().ok()));
+    let radicals_fetched = use_radicals();
+    let characters_fetched = use_characters();
+    view! {
+        <main>
+            <Title text="中 Char"/>
+            <Nav/>
+            <div class="w-full h-dvh absolute top-0 flex flex-col">
+                <div class="w-full h-16 flex-none"></div>
+                <div class="w-full flex-grow overflow-y-auto p-4 flex flex-col lg:flex-row gap-8">
+                    <section class="flex flex-col gap-2 lg:w-1/2">
+                        <h2 class="text-lg font-bold">"Radicals by stroke count"</h2>
+                        <Suspense fallback=move || view! {
+                            <div class="flex flex-col gap-2">
+                                <div class="skeleton w-full h-64"></div>
+                                <span class="opacity-70"><LoadingStatus/></span>
+                            </div>
+                        }>
+                            <ErrorBoundary fallback=move |errors| load_error_view("the radicals", errors, move || radicals_fetched.refetch())>
+                                {move || Suspend::new(async move {
+                                    radicals_fetched.await.map(|radicals| radical_index_view(&radicals, selected))
+                                })}
+                            </ErrorBoundary>
+                        </Suspense>
+                    </section>
+                    <section class="flex flex-col gap-2 lg:w-1/2 lg:order-none" class=("order-first", move || selected.get().is_some())>
+                        <h2 class="text-lg font-bold">"Characters by remaining strokes"</h2>
+                        <Show
+                            when=move || selected.get().is_some()
+                            fallback=|| view! { <p class="opacity-70">"Choose a radical to see the characters filed under it."</p> }
+                        >
+                            <Suspense fallback=move || view! {
+                                <div class="flex flex-col gap-2">
+                                    <div class="skeleton w-full h-40"></div>
+                                    <span class="opacity-70"><LoadingStatus/></span>
+                                </div>
+                            }>
+                                <ErrorBoundary fallback=move |errors| load_error_view("the characters", errors, move || {
+                                    radicals_fetched.refetch();
+                                    characters_fetched.refetch();
+                                })>
+                                    { move || {
+                                        let requested = selected.get();
+                                        Suspend::new(async move {
+                                            let radicals = radicals_fetched.await?;
+                                            let characters = characters_fetched.await?;
+                                            let found = requested.and_then(|requested| {
+                                                radicals.iter().find(|radical| radical.number == requested)
+                                            });
+                                            Ok::<_, ArcZhongCharError>(match found {
+                                                Some(radical) => residual_index_view(radical, &characters).into_any(),
+                                                None => view! {
+                                                    <p>"There is no radical number "{requested}"."</p>
+                                                }.into_any(),
+                                            })
+                                        })
+                                    }}
+                                </ErrorBoundary>
+                            </Suspense>
+                        </Show>
+                    </section>
+                </div>
+            </div>
+        </main>
+    }
+}
+
+#[component]
+fn Flashcards() -> impl IntoView {
+    let radicals_fetched = use_radicals();
+    // Study still works without saved history, it just won't be kept.
+    let card_states = LocalResource::new(|| async {
+        read_card_states().await.unwrap_or_else(|error| {
+            log!("could not read flashcard history: {}", error);
+            Default::default()
+        })
+    });
+    view! {
+        <main>
+            <Title text="中 Char"/>
+            <Nav/>
+            <div class="w-full h-dvh absolute top-0 flex flex-col">
+                <div class="w-full h-16 flex-none"></div>
+                <div class="w-full flex-grow overflow-y-auto p-4">
+                    <Suspense fallback=move || view! {
+                        <div class="flex flex-col gap-2">
+                            <div class="skeleton w-64 h-40"></div>
+                            <span class="opacity-70"><LoadingStatus/></span>
+                        </div>
+                    }>
+                        <ErrorBoundary fallback=move |errors| load_error_view("the radicals", errors, move || radicals_fetched.refetch())>
+                            {move || Suspend::new(async move {
+                                let radicals = radicals_fetched.await?;
+                                let states = card_states.await;
+                                Ok::<_, ArcZhongCharError>(view! { <FlashcardSession radicals=radicals states=states/> })
+                            })}
+                        </ErrorBoundary>
+                    </Suspense>
+                </div>
+            </div>
+        </main>
+    }
+}
+
+#[component]
+fn Quiz() -> impl IntoView {
+    let radicals_fetched = use_radicals();
+    view! {
+        <main>
+            <Title text="中 Char"/>
+            <Nav/>
+            <div class="w-full h-dvh absolute top-0 flex flex-col">
+                <div class="w-full h-16 flex-none"></div>
+                <div class="w-full flex-grow overflow-y-auto p-4">
+                    <Suspense fallback=move || view! {
+                        <div class="flex flex-col gap-2">
+                            <div class="skeleton w-64 h-40"></div>
+                            <span class="opacity-70"><LoadingStatus/></span>
+                        </div>
+                    }>
+                        <ErrorBoundary fallback=move |errors| load_error_view("the radicals", errors, move || radicals_fetched.refetch())>
+                            {move || Suspend::new(async move {
+                                radicals_fetched.await.map(|radicals| view! { <QuizRunner radicals=radicals/> })
+                            })}
+                        </ErrorBoundary>
+                    </Suspense>
+                </div>
+            </div>
+        </main>
+    }
+}
+
+#[component]
+fn Data() -> impl IntoView {
+    let radicals_fetched = use_radicals();
+    view! {
+        <main>
+            <Title text="中 Char"/>
+            <Nav/>
+            <div class="w-full h-dvh absolute top-0 flex flex-col">
+                <div class="w-full h-16 flex-none"></div>
+                <div class="w-full flex-grow overflow-y-auto p-4 flex flex-col gap-8">
+                    <section class="flex flex-col gap-2">
+                        <h2 class="text-lg font-bold">"Export to Anki"</h2>
+                        <Suspense fallback=move || view! {
+                            <div class="flex flex-col gap-2">
+                                <div class="skeleton w-full max-w-2xl h-40"></div>
+                                <span class="opacity-70"><LoadingStatus/></span>
+                            </div>
+                        }>
+                            <ErrorBoundary fallback=move |errors| load_error_view("the radicals", errors, move || radicals_fetched.refetch())>
+                                {move || Suspend::new(async move {
+                                    radicals_fetched.await.map(|radicals| view! { <AnkiExport radicals=radicals/> })
+                                })}
+                            </ErrorBoundary>
+                        </Suspense>
+                    </section>
+                    <section class="flex flex-col gap-2">
+                        <h2 class="text-lg font-bold">"Backup and restore"</h2>
+                        <BackupRestore/>
+                    </section>
+                </div>
+            </div>
+        </main>
+    }
+}
+
+#[component]
+fn CharacterDetail() -> impl IntoView {
+    let params = use_params_map();
+    let requested_character = move || params.read().get("c").and_then(|c| c.chars().next());
+    let radicals_fetched = use_radicals();
+    let characters_fetched = use_characters();
+    let script_map_built = use_script_map(radicals_fetched, characters_fetched);
+    provide_context(PageGraphics(use_character_graphics(requested_character)));
+    view! {
+        <main>
+            <Title text="中 Char"/>
+            <Nav/>
+            <div class="w-full h-dvh absolute top-0 flex flex-col">
+                <div class="w-full h-16 flex-none"></div>
+                <div class="w-full flex-grow overflow-y-auto p-4">
+                    <Suspense fallback=move || view! {
+                        <div class="flex flex-col gap-2">
+                            <div class="skeleton w-40 h-40"></div>
+                            <span class="opacity-70"><LoadingStatus/></span>
+                        </div>
+                    }>
+                        <ErrorBoundary fallback=move |errors| load_error_view("this character", errors, move || {
+                            radicals_fetched.refetch();
+                            characters_fetched.refetch();
+                        })>
+                            { move || {
+                                let requested = requested_character();
+                                Suspend::new(async move {
+                                    let radicals = radicals_fetched.await?;
+                                    let characters = characters_fetched.await?;
+                                    let script_map = script_map_built.await?;
+                                    let found = requested.and_then(|requested| {
+                                        characters.into_iter().find(|character| character.character == requested)
+                                    });
+                                    Ok::<_, ArcZhongCharError>(match found {
+                                        Some(character) => {
+                                            let radical = radicals.iter().find(|radical| radical.is_written_as(character.radical));
+                                            character_detail_view(character, radical.map(|radical| radical.number), &script_map).into_any()
+                                        }
+                                        None => character_not_found_view(requested).into_any(),
+                                    })
+                                })
+                            }}
+                        </ErrorBoundary>
+                    </Suspense>
+                </div>
+            </div>
+        </main>
+    }
+}
+
+/// `radical_number` is the Kangxi radical the character is filed under, if
+/// the radical table has it.
+fn character_detail_view(character: Character, radical_number: Option<i32>, script_map: &ScriptMap) -> impl IntoView {
+    // The decomposition node under the pointer, whose strokes are highlighted.
+    let highlighted = RwSignal::new(None::<ComponentPath>);
+    let decomposition = match character.parsed_decomposition() {
+        Ok(decomposition) if !decomposition.is_unknown() => view! {
+            <div class="flex flex-wrap items-start gap-6">
+                <ul class="menu menu-sm bg-base-200 rounded-box w-fit">
+                    <li>{decomposition_tree_view(decomposition, Vec::new(), character.etymology.as_ref(), highlighted)}</li>
+                </ul>
+                <ComponentStrokes character=character.clone() highlighted=highlighted/>
+            </div>
+        }.into_any(),
+        Ok(_) => view! { <p class="opacity-60">"Unknown"</p> }.into_any(),
+        Err(error) => view! {
+            <p class="opacity-60">{character.decomposition.clone()}" ("{error.to_string()}")"</p>
+        }.into_any(),
+    };
+    let etymology = character.etymology.clone().map(|etymology| {
+        let kind = match &etymology {
+            Etymology::Ideographic { .. } => "Ideographic",
+            Etymology::Pictographic { .. } => "Pictographic",
+            Etymology::Pictophonetic { .. } => "Pictophonetic",
+        };
+        let parts = match &etymology {
+            Etymology::Pictophonetic { semantic, phonetic, .. } => Some(view! {
+                <dl class="grid grid-cols-[auto_1fr] gap-x-4">
+                    {semantic.clone().map(|semantic| view! {
+                        <dt class="font-semibold">"Semantic"</dt>
+                        <dd>{component_links_view(&semantic)}</dd>
+                    })}
+                    {phonetic.clone().map(|phonetic| view! {
+                        <dt class="font-semibold">"Phonetic"</dt>
+                        <dd>{component_links_view(&phonetic)}</dd>
+                    })}
+                </dl>
+            }),
+            _ => None,
+        };
+        let hint = etymology.hint().map(|hint| view! { <p>{hint.to_string()}</p> });
+        view! {
+            <section class="flex flex-col gap-2">
+                <h2 class="text-lg font-bold">"Etymology"</h2>
+                <span class="badge badge-outline">{kind}</span>
+                {parts}
+                {hint}
+            </section>
+        }
+    });
+
+    view! {
+        <div class="flex flex-col gap-6 max-w-2xl">
+            <div class="flex items-end gap-6">
+                <span class="text-9xl leading-none">{character.character.to_string()}</span>
+                <div class="flex flex-col gap-1">
+                    <span class="text-2xl">{character.pinyin.join(", ")}</span>
+                    <span>
+                        "Radical "
+                        {match radical_number {
+                            Some(number) => view! {
+                                <a class="link" href=radical_href(number)>{character.radical.to_string()}</a>
+                            }.into_any(),
+                            None => view! { {character.radical.to_string()} }.into_any(),
+                        }}
+                    </span>
+                    {script_counterparts_view(character.character, script_map)}
+                </div>
+            </div>
+            <section class="flex flex-col gap-2">
+                <h2 class="text-lg font-bold">"Definition"</h2>
+                <p>{character.definition.clone().unwrap_or_else(|| "No definition".to_string())}</p>
+            </section>
+            <section class="flex flex-col gap-2">
+                <h2 class="text-lg font-bold">"Decomposition"</h2>
+                {decomposition}
+            </section>
+            <StrokeOrderSection/>
+            {etymology}
+        </div>
+    }
+}
+
+/// Renders one node of the decomposition and its children. `path` is the
+/// node's position in the same form as `Character::matches`, so hovering a
+/// node can highlight its strokes.
+fn decomposition_tree_view(
+    decomposition: Decomposition,
+    path: ComponentPath,
+    etymology: Option<&Etymology>,
+    highlighted: RwSignal<Option<ComponentPath>>,
+) -> AnyView {
+    let highlight = {
+        let path = path.clone();
+        move |_: MouseEvent| highlighted.set(Some(path.clone()))
+    };
+    let clear = move |_: MouseEvent| highlighted.set(None);
+    match decomposition {
+        Decomposition::Component(component) => {
+            let role_class = etymology.and_then(|etymology| etymology.role_of(component)).map(role_text_class).unwrap_or_default();
+            view! {
+                <a href=character_href(component) class=role_class on:mouseenter=highlight on:mouseleave=clear>
+                    {component.to_string()}
+                </a>
+            }.into_any()
+        }
+        Decomposition::Unknown => view! {
+            <span class="opacity-60" on:mouseenter=highlight on:mouseleave=clear>{UNKNOWN_COMPONENT.to_string()}</span>
+        }.into_any(),
+        Decomposition::Compound { operator, components } => view! {
+            <span title=operator.description() on:mouseenter=highlight on:mouseleave=clear>
+                {operator.to_char().to_string()}
+            </span>
+            <ul>
+                {components.into_iter().enumerate().map(|(index, component)| {
+                    let mut child_path = path.clone();
+                    child_path.push(index);
+                    view! {
+                        <li>{decomposition_tree_view(component, child_path, etymology, highlighted)}</li>
+                    }
+                }).collect::<Vec<_>>()}
+            </ul>
+        }.into_any(),
+    }
+}
+
+fn component_links_view(components: &str) -> impl IntoView {
+    components.chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|component| view! {
+            <a class="link mr-1" href=character_href(component)>{component.to_string()}</a>
+        })
+        .collect::<Vec<_>>()
+}
+
+fn character_not_found_view(requested: Option<char>) -> impl IntoView {
+    let base_url = option_env!("BASE_URL").unwrap_or("/");
+    let requested = requested.map(|c| c.to_string()).unwrap_or_default();
+    view! {
+        <div class="flex flex-col gap-4 max-w-2xl">
+            <span class="text-9xl leading-none opacity-40">{requested.clone()}</span>
+            <p>"\u{201C}"{requested}"\u{201D} isn't in the dictionary yet."</p>
+            <a class="link" href=format!("{}{}", base_url, "radicals")>"Browse the radicals instead"</a>
+        </div>
+    }
+}
+
+
+/// What the dataset downloads are up to, so a slow connection shows more than
+/// a skeleton: which file, how long it has taken and whether it's retrying.
+#[component]
+pub(crate) fn LoadingStatus() -> impl IntoView {
+    let progress = use_context::<DatasetProgress>();
+    let now = RwSignal::new(js_sys::Date::now());
+    let _ = use_interval_fn(move || now.set(js_sys::Date::now()), 1000);
+    move || {
+        let downloads = progress.map(|progress| progress.0.get()).unwrap_or_default();
+        if downloads.is_empty() {
+            return "\u{00A0}".to_string();
+        }
+        downloads.iter()
+            .map(|download| {
+                let seconds = ((now.get() - download.started_at) / 1000.0).max(0.0) as u64;
+                let mut status = format!("Downloading {}… {} s", download.file_name, seconds);
+                if download.attempt > 1 {
+                    status.push_str(&format!(", attempt {} of {}", download.attempt, download.max_attempts));
+                }
+                status
+            })
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+}
+
+/// Fallback for a page whose datasets failed to load: a plain account of
+/// what went wrong, the underlying messages for bug reports, and a retry.
+fn load_error_view(
+    what: &'static str,
+    errors: ArcRwSignal<Errors>,
+    retry: impl Fn() + Send + Sync + 'static,
+) -> impl IntoView {
+    view! {
+        <div role="alert" class="alert alert-error flex flex-col items-start gap-2 max-w-2xl">
+            <p class="font-bold">"Could not load "{what}"."</p>
+            {move || errors.get()
+                .into_iter()
+                .map(|(_, error)| {
+                    let summary = match error.downcast_ref::<ArcZhongCharError>() {
+                        Some(ArcZhongCharError::Error(error)) => error_summary(error),
+                        None => "Something went wrong while loading.".to_string(),
+                    };
+                    view! {
+                        <div>
+                            <p>{summary}</p>
+                            <p class="text-sm opacity-80">{error.to_string()}</p>
+                        </div>
+                    }
+                })
+                .collect::<Vec<_>>()
+            }
+            <button class="btn btn-sm" on:click=move |_| retry()>"Retry"</button>
+        </div>
+    }
+}
+
+fn error_summary(error: &ZhongCharError) -> String {
+    match error {
+        ZhongCharError::Http { status: 404, .. } => {
+            "A data file is missing from the server. The site may be partway through an update.".to_string()
+        }
+        ZhongCharError::Http { status, .. } => format!("The server could not send the data (HTTP {}).", status),
+        ZhongCharError::Timeout { .. } => {
+            "The server kept taking too long to respond, even after several attempts.".to_string()
+        }
+        ZhongCharError::Network(_) => {
+            "The server could not be reached. Check your connection; pages you have opened before work offline.".to_string()
+        }
+        ZhongCharError::Csv(_) | ZhongCharError::CsvField { .. } => {
+            format!("{} is malformed and could not be read.", Radical::DATASET)
+        }
+        ZhongCharError::DictionaryLine { .. } => {
+            format!("{} is malformed and could not be read.", Character::DATASET)
+        }
+        ZhongCharError::GraphicsLine { .. } => {
+            "The stroke data is malformed and could not be read.".to_string()
+        }
+        ZhongCharError::IndexedDb(_) => "The browser's offline storage failed.".to_string(),
+        ZhongCharError::Wasm(_) | ZhongCharError::Ids(_) | ZhongCharError::Pinyin(_) => {
+            "Something went wrong while loading.".to_string()
+        }
+    }
+}
+
+/// A dataset served from IndexedDB, refetched if a background check finds
+/// `file_name` has changed since it was cached.
+fn use_dataset<T>(
+    file_name: &'static str,
+    parse: fn(&str) -> ZhongCharResult<Vec<T>>,
+) -> LocalResource<Result<Vec<T>, ArcZhongCharError>>
+where
+    T: Serialize + DeserializeOwned + 'static,
+{
+    let refreshed = Trigger::new();
+    let policy = use_context::<FetchPolicy>().unwrap_or_default();
+    let progress = use_context::<DatasetProgress>();
+    LocalResource::new(move || {
+        refreshed.track();
+        async move {
+            let on_progress = move |update| {
+                if let Some(progress) = progress {
+                    progress.report(file_name, update);
+                }
+            };
+            load_dataset(file_name, parse, policy, on_progress, move || refreshed.notify())
+                .await
+                .map_err(ArcZhongCharError::from)
+        }
+    })
+}
+
+fn use_radicals() -> LocalResource<Result<Vec<Radical>, ArcZhongCharError>> {
+    use_dataset(Radical::DATASET, Radical::parse_radicals)
+}
+
+fn use_characters() -> LocalResource<Result<Vec<Character>, ArcZhongCharError>> {
+    use_dataset(Character::DATASET, Character::parse_dictionary)
+}
+
+/// The traditional/simplified map for the loaded datasets. Building it walks
+/// the whole dictionary, so it's done once per load rather than on every
+/// render of the page showing it.
+fn use_script_map(
+    radicals: LocalResource<Result<Vec<Radical>, ArcZhongCharError>>,
+    characters: LocalResource<Result<Vec<Character>, ArcZhongCharError>>,
+) -> LocalResource<Result<Arc<ScriptMap>, ArcZhongCharError>> {
+    LocalResource::new(move || async move {
+        let radicals = radicals.await?;
+        let characters = characters.await?;
+        Ok(Arc::new(ScriptMap::build(&radicals, &characters)))
+    })
+}
+
+/// Stroke graphics for one character, `None` if the site has none for it.
+pub(crate) type GraphicsResource = LocalResource<Result<Option<CharacterGraphics>, ArcZhongCharError>>;
+
+/// The character page's graphics, loaded once and provided as context to the
+/// sections that draw the character.
+#[derive(Clone, Copy)]
+pub(crate) struct PageGraphics(pub GraphicsResource);
+
+/// Loads the graphics of whichever character `character` returns, refetched
+/// if a background check finds its file has changed.
+fn use_character_graphics(character: impl Fn() -> Option<char> + 'static) -> GraphicsResource {
+    let refreshed = Trigger::new();
+    let policy = use_context::<FetchPolicy>().unwrap_or_default();
+    let progress = use_context::<DatasetProgress>();
+    LocalResource::new(move || {
+        refreshed.track();
+        let character = character();
+        async move {
+            let Some(character) = character else { return Ok(None) };
+            let file_name = CharacterGraphics::file_name(character);
+            let on_progress = move |update| {
+                if let Some(progress) = progress {
+                    progress.report(&file_name, update);
+                }
+            };
+            load_character_graphics(character, policy, on_progress, move || refreshed.notify())
+                .await
+                .map_err(ArcZhongCharError::from)
+        }
+    })
+}
+
 pub fn use_element_focus<El, M>(
     el: El,
 ) -> Signal<bool>
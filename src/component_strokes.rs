@@ -0,0 +1,102 @@
+use leptos::prelude::*;
+use zhongchar_core::character::{Character, ComponentPath, ComponentRole};
+use zhongchar_core::graphics::CharacterGraphics;
+
+use crate::app::PageGraphics;
+
+/// Fills for components the etymology doesn't name, by top-level position.
+const COMPONENT_FILLS: [&str; 4] = ["fill-accent", "fill-info", "fill-success", "fill-warning"];
+/// Strokes `matches` doesn't assign to any component.
+const UNMATCHED_FILL: &str = "fill-base-content";
+
+fn role_fill(role: ComponentRole) -> &'static str {
+    match role {
+        ComponentRole::Semantic => "fill-primary",
+        ComponentRole::Phonetic => "fill-secondary",
+    }
+}
+
+pub(crate) fn role_text_class(role: ComponentRole) -> &'static str {
+    match role {
+        ComponentRole::Semantic => "text-primary",
+        ComponentRole::Phonetic => "text-secondary",
+    }
+}
+
+/// The character drawn with each stroke coloured by the component it belongs
+/// to. While `highlighted` holds a decomposition path, strokes outside that
+/// component fade out.
+///
+/// Draws from the graphics the character page provides as `PageGraphics`; if
+/// they can't be loaded this renders nothing and leaves the stroke order
+/// section to explain.
+#[component]
+pub(crate) fn ComponentStrokes(character: Character, highlighted: RwSignal<Option<ComponentPath>>) -> impl IntoView {
+    let PageGraphics(graphics_fetched) = expect_context::<PageGraphics>();
+    let character = StoredValue::new(character);
+    view! {
+        <Suspense fallback=|| view! { <div class="skeleton w-32 h-32"></div> }>
+            <ErrorBoundary fallback=|_| ()>
+                {move || Suspend::new(async move {
+                    graphics_fetched.await.map(|graphics| {
+                        graphics.map(|graphics| character.with_value(|character| {
+                            component_strokes_view(character, graphics, highlighted)
+                        }))
+                    })
+                })}
+            </ErrorBoundary>
+        </Suspense>
+    }
+}
+
+fn component_strokes_view(
+    character: &Character,
+    graphics: CharacterGraphics,
+    highlighted: RwSignal<Option<ComponentPath>>,
+) -> impl IntoView {
+    let roles = character.parsed_decomposition()
+        .map(|decomposition| character.stroke_roles(&decomposition))
+        .unwrap_or_default();
+    let has_role = |wanted: ComponentRole| roles.iter().any(|role| *role == Some(wanted));
+    let legend = [ComponentRole::Semantic, ComponentRole::Phonetic]
+        .into_iter()
+        .filter(|role| has_role(*role))
+        .map(|role| {
+            let (label, badge) = match role {
+                ComponentRole::Semantic => ("Semantic", "badge badge-sm badge-primary"),
+                ComponentRole::Phonetic => ("Phonetic", "badge badge-sm badge-secondary"),
+            };
+            view! { <span class=badge>{label}</span> }
+        })
+        .collect::<Vec<_>>();
+
+    let strokes = graphics.strokes.into_iter()
+        .enumerate()
+        .map(|(stroke, outline)| {
+            let component = character.matches.get(stroke).cloned().flatten();
+            let fill = match (roles.get(stroke).copied().flatten(), &component) {
+                (Some(role), _) => role_fill(role),
+                (None, Some(path)) => COMPONENT_FILLS[path.first().copied().unwrap_or_default() % COMPONENT_FILLS.len()],
+                (None, None) => UNMATCHED_FILL,
+            };
+            let class = move || {
+                let dimmed = highlighted.with(|highlighted| {
+                    highlighted.as_ref().is_some_and(|highlighted| {
+                        !component.as_ref().is_some_and(|component| component.starts_with(highlighted))
+                    })
+                });
+                format!("transition-opacity {}{}", fill, if dimmed { " opacity-20" } else { "" })
+            };
+            view! { <path d=outline class=class/> }
+        })
+        .collect::<Vec<_>>();
+
+    view! {
+        <div class="flex flex-col gap-2 w-fit">
+            <svg viewBox=CharacterGraphics::SVG_VIEW_BOX class="w-32 h-32 bg-base-200 rounded-box">
+                <g transform=CharacterGraphics::SVG_TRANSFORM>{strokes}</g>
+            </svg>
+            <div class="flex gap-1">{legend}</div>
+        </div>
+    }
+}
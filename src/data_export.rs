@@ -0,0 +1,241 @@
+use gloo_timers::callback::Timeout;
+use leptos::logging::log;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos::wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, HtmlInputElement, Url};
+use zhongchar_core::backup::{Backup, RestoreMode};
+use zhongchar_core::error::{ZhongCharError, ZhongCharResult};
+use zhongchar_core::export::{anki_tsv, AnkiTemplates, ExportSource};
+use zhongchar_core::flashcards::CardKind;
+use zhongchar_core::radical::Radical;
+
+use crate::storage::{read_backup, restore_backup};
+
+const RADICAL_TABLE_KEY: &str = "radicals";
+/// How long a download's object URL is kept after the link is clicked.
+const REVOKE_DELAY_MILLIS: u32 = 10_000;
+
+fn source_key(source: ExportSource) -> &'static str {
+    match source {
+        ExportSource::RadicalTable => RADICAL_TABLE_KEY,
+        ExportSource::Deck(kind) => kind.key(),
+    }
+}
+
+fn source_from_key(key: &str) -> Option<ExportSource> {
+    if key == RADICAL_TABLE_KEY {
+        return Some(ExportSource::RadicalTable);
+    }
+    CardKind::from_key(key).map(ExportSource::Deck)
+}
+
+/// Anki export of the radical table or one flashcard deck. The templates are
+/// checked as they're typed, and the download is only offered once all three
+/// are valid.
+#[component]
+pub(crate) fn AnkiExport(radicals: Vec<Radical>) -> impl IntoView {
+    let radicals = StoredValue::new(radicals);
+    let source = RwSignal::new(ExportSource::RadicalTable);
+    let defaults = AnkiTemplates::defaults(ExportSource::RadicalTable);
+    let front = RwSignal::new(defaults.front);
+    let back = RwSignal::new(defaults.back);
+    let tags = RwSignal::new(defaults.tags);
+
+    let choose_source = move |key: String| {
+        let Some(chosen) = source_from_key(&key) else { return };
+        let defaults = AnkiTemplates::defaults(chosen);
+        source.set(chosen);
+        front.set(defaults.front);
+        back.set(defaults.back);
+        tags.set(defaults.tags);
+    };
+    let export = Memo::new(move |_| {
+        let templates = AnkiTemplates { front: front.get(), back: back.get(), tags: tags.get() };
+        radicals.with_value(|radicals| anki_tsv(radicals, source.get(), &templates).map_err(|error| error.to_string()))
+    });
+    let download_tsv = move |_| {
+        let Ok(tsv) = export.get_untracked() else { return };
+        let file_name = format!("zhongchar-{}.tsv", source_key(source.get_untracked()).replace('_', "-"));
+        if let Err(error) = download(&file_name, "text/tab-separated-values", &tsv) {
+            log!("could not download {}: {}", file_name, error);
+        }
+    };
+
+    let template_input = move |label: &'static str, template: RwSignal<String>| view! {
+        <label class="form-control">
+            <span class="label-text">{label}</span>
+            <input class="input input-bordered font-mono" type="text" spellcheck="false" bind:value=template/>
+        </label>
+    };
+
+    view! {
+        <div class="flex flex-col gap-3 max-w-2xl">
+            <label class="form-control w-fit">
+                <span class="label-text">"Export"</span>
+                <select class="select select-bordered" on:change=move |ev| choose_source(event_target_value(&ev))>
+                    <option value=RADICAL_TABLE_KEY selected=true>"The radical table"</option>
+                    {CardKind::ALL.into_iter().map(|kind| view! {
+                        <option value=kind.key()>{format!("Flashcards: {}", kind)}</option>
+                    }).collect::<Vec<_>>()}
+                </select>
+            </label>
+            {template_input("Front", front)}
+            {template_input("Back", back)}
+            {template_input("Tags", tags)}
+            <p class="text-sm opacity-70">
+                "Write "<code>"{field}"</code>" to insert a field, and "<code>"{{"</code>" or "<code>"}}"</code>
+                " for a literal brace. HTML such as "<code>"<br>"</code>" is kept. Available fields: "
+                {move || source.get().fields().join(", ")}
+            </p>
+            {move || export.get().err().map(|error| view! { <div role="alert" class="alert alert-error">{error}</div> })}
+            <button class="btn btn-primary w-fit" disabled=move || export.with(|export| export.is_err()) on:click=download_tsv>
+                "Download TSV"
+            </button>
+        </div>
+    }
+}
+
+/// A file chosen for restoring, once read and checked.
+#[derive(Clone)]
+enum RestoreFile {
+    Valid(Backup),
+    Invalid(String),
+}
+
+/// Download of a full backup, and restore from one.
+#[component]
+pub(crate) fn BackupRestore() -> impl IntoView {
+    let status = RwSignal::new(None::<Result<String, String>>);
+    let chosen = RwSignal::new(None::<RestoreFile>);
+    let mode = RwSignal::new(RestoreMode::Merge);
+    let busy = RwSignal::new(false);
+
+    let download_backup = move |_| {
+        busy.set(true);
+        spawn_local(async move {
+            let result = async {
+                let backup = read_backup().await?;
+                let date = String::from(js_sys::Date::new_0().to_iso_string());
+                let file_name = format!("zhongchar-backup-{}.json", &date[..10]);
+                download(&file_name, "application/json", &backup.to_json())?;
+                Ok::<_, ZhongCharError>(format!("Saved {}.", file_name))
+            }.await;
+            status.set(Some(result.map_err(|error| format!("Could not make a backup: {}", error))));
+            busy.set(false);
+        });
+    };
+    let choose_file = move |ev: leptos::ev::Event| {
+        let input = event_target::<HtmlInputElement>(&ev);
+        let Some(file) = input.files().and_then(|files| files.get(0)) else {
+            chosen.set(None);
+            return;
+        };
+        spawn_local(async move {
+            let read = match JsFuture::from(file.text()).await {
+                Ok(text) => match Backup::parse(&text.as_string().unwrap_or_default()) {
+                    Ok(backup) => RestoreFile::Valid(backup),
+                    Err(error) => RestoreFile::Invalid(error.to_string()),
+                },
+                Err(error) => RestoreFile::Invalid(format!("{:?}", error)),
+            };
+            chosen.set(Some(read));
+        });
+    };
+    let restore = move |_| {
+        let Some(RestoreFile::Valid(backup)) = chosen.get_untracked() else { return };
+        busy.set(true);
+        spawn_local(async move {
+            let result = restore_backup(backup, mode.get_untracked()).await
+                .map(|restored| format!("Restored. You now have {}.", backup_summary(&restored)))
+                .map_err(|error| format!("Could not restore the backup: {}", error));
+            status.set(Some(result));
+            busy.set(false);
+        });
+    };
+
+    view! {
+        <div class="flex flex-col gap-4 max-w-2xl">
+            <p>"A backup holds your flashcard progress, review history, tracing results and study settings."</p>
+            <button class="btn btn-primary w-fit" disabled=move || busy.get() on:click=download_backup>
+                "Download backup"
+            </button>
+            <label class="form-control w-fit">
+                <span class="label-text">"Restore from a backup"</span>
+                <input class="file-input file-input-bordered" type="file" accept=".json,application/json" on:change=choose_file/>
+            </label>
+            {move || chosen.get().map(|file| match file {
+                RestoreFile::Invalid(error) => view! {
+                    <div role="alert" class="alert alert-error">"This file can't be restored: "{error}</div>
+                }.into_any(),
+                RestoreFile::Valid(backup) => {
+                    let exported_at = js_sys::Date::new(&JsValue::from_f64(backup.exported_at))
+                        .to_locale_string("default", &JsValue::UNDEFINED);
+                    view! {
+                        <div class="flex flex-col gap-3">
+                            <p>"Backup from "{String::from(exported_at)}" with "{backup_summary(&backup)}"."</p>
+                            <label class="label cursor-pointer justify-start gap-2">
+                                <input type="radio" class="radio radio-sm" name="restore-mode"
+                                    prop:checked=move || mode.get() == RestoreMode::Merge
+                                    on:change=move |_| mode.set(RestoreMode::Merge)
+                                />
+                                <span>"Merge with the data on this device, keeping the most recent progress"</span>
+                            </label>
+                            <label class="label cursor-pointer justify-start gap-2">
+                                <input type="radio" class="radio radio-sm" name="restore-mode"
+                                    prop:checked=move || mode.get() == RestoreMode::Replace
+                                    on:change=move |_| mode.set(RestoreMode::Replace)
+                                />
+                                <span>"Replace the data on this device"</span>
+                            </label>
+                            <button class="btn w-fit"
+                                class:btn-error=move || mode.get() == RestoreMode::Replace
+                                disabled=move || busy.get()
+                                on:click=restore
+                            >
+                                "Restore"
+                            </button>
+                        </div>
+                    }.into_any()
+                }
+            })}
+            {move || status.get().map(|status| match status {
+                Ok(message) => view! { <div role="status" class="alert alert-success">{message}</div> }.into_any(),
+                Err(message) => view! { <div role="alert" class="alert alert-error">{message}</div> }.into_any(),
+            })}
+        </div>
+    }
+}
+
+fn backup_summary(backup: &Backup) -> String {
+    format!(
+        "{} scheduled cards, {} reviews and tracing results for {} characters",
+        backup.cards.len(),
+        backup.reviews.len(),
+        backup.tracing.len(),
+    )
+}
+
+/// Hands `contents` to the browser as a file download.
+fn download(file_name: &str, mime_type: &str, contents: &str) -> ZhongCharResult<()> {
+    let wasm_error = |error: JsValue| ZhongCharError::Wasm(format!("{:?}", error));
+    let parts = js_sys::Array::of1(&JsValue::from_str(contents));
+    let options = BlobPropertyBag::new();
+    options.set_type(mime_type);
+    let blob = Blob::new_with_str_sequence_and_options(&parts, &options).map_err(wasm_error)?;
+    let url = Url::create_object_url_with_blob(&blob).map_err(wasm_error)?;
+    let anchor = document().create_element("a").map_err(wasm_error)?.unchecked_into::<HtmlAnchorElement>();
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+    // Some browsers start the download after `click` returns, so give them a
+    // moment before the URL stops working.
+    Timeout::new(REVOKE_DELAY_MILLIS, move || {
+        if let Err(error) = Url::revoke_object_url(&url) {
+            log!("could not revoke {}: {:?}", url, error);
+        }
+    })
+    .forget();
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use gloo_timers::callback::Timeout;
+use gloo_timers::future::sleep;
+use leptos::logging::log;
+use leptos::prelude::*;
+use web_sys::AbortController;
+use zhongchar_core::error::{ZhongCharError, ZhongCharResult};
+
+use crate::helpers::dataset_url;
+
+/// How dataset downloads cope with slow or flaky connections. Provide one as
+/// context to override the defaults.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct FetchPolicy {
+    /// How long a single attempt may wait for the response to start. Reading
+    /// the body isn't limited, so a large file can finish on a slow link.
+    pub timeout: Duration,
+    pub max_attempts: u32,
+    /// Wait before the second attempt, doubled after each failure.
+    pub initial_backoff: Duration,
+}
+
+impl Default for FetchPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(20),
+            max_attempts: 4,
+            initial_backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Where a foreground download has got to.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct FetchProgress {
+    pub file_name: String,
+    pub attempt: u32,
+    pub max_attempts: u32,
+    /// `Date.now()` when the first attempt started.
+    pub started_at: f64,
+}
+
+/// Downloads in flight, for loading indicators.
+#[derive(Clone, Copy)]
+pub(crate) struct DatasetProgress(pub RwSignal<Vec<FetchProgress>>);
+
+impl DatasetProgress {
+    pub(crate) fn report(&self, file_name: &str, progress: Option<FetchProgress>) {
+        self.0.update(|downloads| {
+            downloads.retain(|download| download.file_name != file_name);
+            downloads.extend(progress);
+        });
+    }
+}
+
+/// A downloaded file with the `ETag` the server sent for it, if any.
+#[derive(Default)]
+pub(crate) struct Download {
+    pub text: String,
+    pub etag: Option<String>,
+}
+
+/// Fetches a dataset from the site root, retrying transient failures with
+/// exponential backoff. `on_progress` hears about each attempt, then `None`
+/// once the download has succeeded or given up. When `etag` is given the file
+/// is only downloaded if it has changed since, and `None` is returned if it
+/// hasn't.
+pub(crate) async fn fetch_dataset(
+    file_name: &str,
+    etag: Option<&str>,
+    policy: FetchPolicy,
+    on_progress: impl Fn(Option<FetchProgress>),
+) -> ZhongCharResult<Option<Download>> {
+    let url = dataset_url(file_name);
+    let started_at = js_sys::Date::now();
+    let mut backoff = policy.initial_backoff;
+    let mut attempt = 1;
+    let result = loop {
+        on_progress(Some(FetchProgress {
+            file_name: file_name.to_string(),
+            attempt,
+            max_attempts: policy.max_attempts,
+            started_at,
+        }));
+        match fetch_once(&url, etag, policy.timeout).await {
+            Err(error) if is_transient(&error) && attempt < policy.max_attempts => {
+                log!("fetching {} failed on attempt {}, retrying in {:?}: {}", file_name, attempt, backoff, error);
+                sleep(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            result => break result,
+        }
+    };
+    on_progress(None);
+    result
+}
+
+async fn fetch_once(url: &str, etag: Option<&str>, timeout: Duration) -> ZhongCharResult<Option<Download>> {
+    let controller = AbortController::new().map_err(|error| ZhongCharError::Wasm(format!("{:?}", error)))?;
+    let timed_out = Rc::new(Cell::new(false));
+    // Dropped once the headers arrive, which cancels it.
+    let timer = {
+        let controller = controller.clone();
+        let timed_out = timed_out.clone();
+        Timeout::new(timeout.as_millis() as u32, move || {
+            timed_out.set(true);
+            controller.abort();
+        })
+    };
+    let network_error = |error: reqwasm::Error| {
+        if timed_out.get() {
+            ZhongCharError::Timeout { url: url.to_string(), seconds: timeout.as_secs() }
+        } else {
+            ZhongCharError::Network(error.to_string())
+        }
+    };
+    let mut request = reqwasm::http::Request::get(url).abort_signal(Some(&controller.signal()));
+    if let Some(etag) = etag {
+        request = request.header("If-None-Match", etag);
+    }
+    let response = request.send().await.map_err(network_error)?;
+    drop(timer);
+    if response.status() == 304 {
+        return Ok(None);
+    }
+    // A 404 page would otherwise reach the parser and fail as malformed data.
+    if !response.ok() {
+        return Err(ZhongCharError::Http { url: url.to_string(), status: response.status() });
+    }
+    let etag = response.headers().get("ETag");
+    let text = response.text().await.map_err(network_error)?;
+    Ok(Some(Download { text, etag }))
+}
+
+/// Failures worth another attempt: the connection dropped, the server was
+/// too slow, or it reported a temporary problem.
+fn is_transient(error: &ZhongCharError) -> bool {
+    match error {
+        ZhongCharError::Network(_) | ZhongCharError::Timeout { .. } => true,
+        ZhongCharError::Http { status, .. } => *status >= 500 || *status == 408 || *status == 429,
+        _ => false,
+    }
+}
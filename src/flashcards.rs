@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+
+use leptos::ev::keydown;
+use leptos::logging::log;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos_use::{use_document, use_event_listener};
+use zhongchar_core::flashcards::{study_queue, Card, CardId, CardKind, ReviewRecord, StudySession};
+use zhongchar_core::radical::Radical;
+use zhongchar_core::scheduler::{CardState, Grade, SchedulerKind, DAY_MS};
+
+use crate::helpers::{radical_href, read_setting, write_setting, CARD_KINDS_SETTING, SCHEDULER_SETTING};
+use crate::storage::record_review;
+
+/// Never-seen cards mixed into one session, on top of the reviews due.
+const NEW_CARDS_PER_SESSION: usize = 10;
+
+fn saved_card_kinds() -> Vec<CardKind> {
+    let saved = read_setting(CARD_KINDS_SETTING)
+        .map(|keys| keys.split(',').filter_map(CardKind::from_key).collect::<Vec<_>>())
+        .unwrap_or_default();
+    if saved.is_empty() { CardKind::ALL.to_vec() } else { saved }
+}
+
+/// Study screen for the radical deck: pick a scheduler and card kinds, then
+/// work through the cards due plus a few new ones. Each answer is scheduled
+/// straight away and saved to IndexedDB.
+#[component]
+pub(crate) fn FlashcardSession(radicals: Vec<Radical>, states: BTreeMap<CardId, CardState>) -> impl IntoView {
+    let radicals = StoredValue::new(radicals);
+    let states = RwSignal::new(states);
+    let scheduler = RwSignal::new(
+        read_setting(SCHEDULER_SETTING).and_then(|key| SchedulerKind::from_key(&key)).unwrap_or_default(),
+    );
+    let card_kinds = RwSignal::new(saved_card_kinds());
+    let session = RwSignal::new(None::<StudySession>);
+    let revealed = RwSignal::new(false);
+
+    Effect::new(move |_| write_setting(SCHEDULER_SETTING, scheduler.get().key()));
+    Effect::new(move |_| {
+        let keys = card_kinds.get().iter().map(|kind| kind.key()).collect::<Vec<_>>().join(",");
+        write_setting(CARD_KINDS_SETTING, &keys);
+    });
+
+    let deck = Memo::new(move |_| radicals.with_value(|radicals| Card::deck(radicals, &card_kinds.get())));
+    let queue_size = move || {
+        let now = js_sys::Date::now();
+        deck.with(|deck| states.with(|states| study_queue(deck, states, now, NEW_CARDS_PER_SESSION).len()))
+    };
+    let due_count = move || {
+        let now = js_sys::Date::now();
+        deck.with(|deck| states.with(|states| {
+            deck.iter().filter(|card| states.get(&card.id).is_some_and(|state| state.is_due(now))).count()
+        }))
+    };
+
+    let start = move |_| {
+        let now = js_sys::Date::now();
+        let queue = deck.with_untracked(|deck| states.with_untracked(|states| {
+            study_queue(deck, states, now, NEW_CARDS_PER_SESSION).into_iter().cloned().collect::<Vec<_>>()
+        }));
+        session.set(Some(StudySession::new(queue)));
+        revealed.set(false);
+    };
+    let answer = move |grade: Grade| {
+        let now = js_sys::Date::now();
+        let Some(card) = session.try_update(|session| session.as_mut().and_then(|session| session.answer(grade))).flatten() else {
+            return;
+        };
+        let scheduler = scheduler.get_untracked();
+        let previous = states.with_untracked(|states| states.get(&card.id).copied());
+        let state = scheduler.scheduler().review(previous.as_ref(), grade, now);
+        states.update(|states| { states.insert(card.id, state); });
+        revealed.set(false);
+        let review = ReviewRecord { card: card.id, grade, reviewed_at: now, scheduler };
+        spawn_local(async move {
+            if let Err(error) = record_review(review, state).await {
+                log!("could not save review of {}: {}", review.card.key(), error);
+            }
+        });
+    };
+
+    // Space or Enter flips the card; 1–4 grade it.
+    let _ = use_event_listener(use_document(), keydown, move |ev| {
+        if session.with_untracked(|session| session.as_ref().is_none_or(StudySession::is_finished)) {
+            return;
+        }
+        match ev.key().as_str() {
+            " " | "Enter" if !revealed.get_untracked() => {
+                ev.prevent_default();
+                revealed.set(true);
+            }
+            key if revealed.get_untracked() => {
+                if let Some(grade) = key.parse::<usize>().ok().and_then(|n| Grade::ALL.get(n.wrapping_sub(1))) {
+                    answer(*grade);
+                }
+            }
+            _ => {}
+        }
+    });
+
+    let settings = move || view! {
+        <div class="flex flex-wrap items-end gap-4">
+            <label class="flex flex-col gap-1">
+                <span class="text-sm opacity-70">"Scheduler"</span>
+                <select class="select select-sm"
+                    on:change=move |ev| {
+                        if let Some(kind) = SchedulerKind::from_key(&event_target_value(&ev)) {
+                            scheduler.set(kind);
+                        }
+                    }
+                >
+                    {SchedulerKind::ALL.into_iter().map(|kind| view! {
+                        <option value=kind.key() selected=move || scheduler.get() == kind>{kind.to_string()}</option>
+                    }).collect::<Vec<_>>()}
+                </select>
+            </label>
+            <div class="flex flex-wrap gap-3">
+                {CardKind::ALL.into_iter().map(|kind| view! {
+                    <label class="label cursor-pointer gap-2">
+                        <input type="checkbox" class="checkbox checkbox-sm"
+                            prop:checked=move || card_kinds.with(|kinds| kinds.contains(&kind))
+                            on:change=move |ev| {
+                                let checked = event_target_checked(&ev);
+                                card_kinds.update(|kinds| {
+                                    kinds.retain(|other| *other != kind);
+                                    if checked {
+                                        kinds.push(kind);
+                                        kinds.sort();
+                                    }
+                                });
+                            }
+                        />
+                        <span>{kind.to_string()}</span>
+                    </label>
+                }).collect::<Vec<_>>()}
+            </div>
+        </div>
+    };
+
+    let start_panel = move || {
+        let summary = session.with(|session| session.as_ref().filter(|session| session.is_finished()).map(|session| {
+            let accuracy = if session.reviewed() == 0 { 0.0 } else { session.correct() as f64 / session.reviewed() as f64 };
+            format!(
+                "Session complete: {} {}, {}% recalled.",
+                session.reviewed(),
+                if session.reviewed() == 1 { "answer" } else { "answers" },
+                (accuracy * 100.0).round(),
+            )
+        }));
+        view! {
+            <div class="flex flex-col gap-4">
+                {summary.map(|summary| view! { <div role="status" class="alert alert-success">{summary}</div> })}
+                {settings}
+                <p>{due_count}" due for review, "{queue_size}" in the next session."</p>
+                <button class="btn btn-primary w-fit" disabled=move || queue_size() == 0 on:click=start>"Start studying"</button>
+            </div>
+        }
+    };
+
+    let card_view = move |card: Card| {
+        let now = js_sys::Date::now();
+        let previous = states.with_untracked(|states| states.get(&card.id).copied());
+        let scheduler = scheduler.get_untracked().scheduler();
+        let grade_buttons = Grade::ALL.into_iter().enumerate().map(|(index, grade)| {
+            let next = scheduler.review(previous.as_ref(), grade, now);
+            let class = if grade.is_correct() { "btn join-item" } else { "btn btn-error join-item" };
+            view! {
+                <button class=class title=format!("Key {}", index + 1) on:click=move |_| answer(grade)>
+                    {grade.label()}
+                    <span class="opacity-60 text-xs">{format_interval((next.due - now) / DAY_MS)}</span>
+                </button>
+            }
+        }).collect::<Vec<_>>();
+        let prompt_class = if card.id.kind.prompt_is_glyph() { "text-9xl leading-none" } else { "text-3xl" };
+        let answer_class = if card.id.kind.answer_is_glyph() { "text-7xl leading-none" } else { "text-2xl" };
+        let remaining = session.with(|session| session.as_ref().map_or(0, StudySession::remaining));
+        view! {
+            <div class="flex flex-col items-center gap-6">
+                <span class="badge badge-outline">{card.id.kind.to_string()}</span>
+                <span class=prompt_class>{card.prompt.clone()}</span>
+                <Show
+                    when=move || revealed.get()
+                    fallback=move || view! {
+                        <button class="btn" title="Space" on:click=move |_| revealed.set(true)>"Show answer"</button>
+                    }
+                >
+                    <span class=answer_class>{card.answer.clone()}</span>
+                    <span class="opacity-70">{card.detail.clone()}</span>
+                    <a class="link link-hover text-sm" href=radical_href(card.id.radical)>"Radical "{card.id.radical}</a>
+                </Show>
+                <div class="join" class:invisible=move || !revealed.get()>{grade_buttons}</div>
+                <span class="opacity-60 text-sm">{remaining}" left"</span>
+            </div>
+        }
+    };
+
+    move || {
+        let current = session.with(|session| session.as_ref().and_then(|session| session.current().cloned()));
+        match current {
+            Some(card) => card_view(card).into_any(),
+            None => start_panel().into_any(),
+        }
+    }
+}
+
+fn format_interval(days: f64) -> String {
+    if days < 1.0 {
+        "<1d".to_string()
+    } else if days < 30.0 {
+        format!("{}d", days.round())
+    } else if days < 365.0 {
+        format!("{}mo", (days / 30.0).round())
+    } else {
+        format!("{}y", (days / 365.0).round())
+    }
+}
@@ -12,4 +12,60 @@ pub(crate) fn prepend_relative_url(relative_url: &str) -> String {
         domain_path = meta_tag.get_attribute("content").unwrap();
     }
     domain_path + relative_url
-}
\ No newline at end of file
+}
+
+pub(crate) fn dataset_url(file_name: &str) -> String {
+    let base_url = option_env!("BASE_URL").unwrap_or("/");
+    let port = window().location().port(); // Get port as Option<String>
+    let port_part = match port {
+        Ok(p) if !p.is_empty() => format!(":{}", p), 
+        _ => "".to_string(),
+    };
+    format!(
+        "{}//{}{}{}{}",
+        window().location().protocol().unwrap(),
+        window().location().hostname().unwrap(),
+        port_part,
+        base_url, 
+        file_name,
+    )
+}
+
+pub(crate) fn character_href(character: char) -> String {
+    let base_url = option_env!("BASE_URL").unwrap_or("/");
+    format!("{}char/{}", base_url, character)
+}
+
+/// Link to one of the app's own pages, e.g. `page_href("flashcards")`.
+pub(crate) fn page_href(path: &str) -> String {
+    let base_url = option_env!("BASE_URL").unwrap_or("/");
+    format!("{}{}", base_url, path)
+}
+
+pub(crate) fn radical_href(number: i32) -> String {
+    let base_url = option_env!("BASE_URL").unwrap_or("/");
+    format!("{}radicals/{}", base_url, number)
+}
+
+pub(crate) const SCHEDULER_SETTING: &str = "flashcards.scheduler";
+/// Comma-separated `CardKind::key`s.
+pub(crate) const CARD_KINDS_SETTING: &str = "flashcards.card_kinds";
+/// Preferences included in a backup.
+pub(crate) const BACKED_UP_SETTINGS: [&str; 2] = [SCHEDULER_SETTING, CARD_KINDS_SETTING];
+
+/// Reads a preference saved with `write_setting`, if local storage is available.
+pub(crate) fn read_setting(key: &str) -> Option<String> {
+    window().local_storage().ok().flatten()?.get_item(key).ok().flatten()
+}
+
+pub(crate) fn write_setting(key: &str, value: &str) {
+    if let Some(storage) = window().local_storage().ok().flatten() {
+        let _ = storage.set_item(key, value);
+    }
+}
+
+pub(crate) fn remove_setting(key: &str) {
+    if let Some(storage) = window().local_storage().ok().flatten() {
+        let _ = storage.remove_item(key);
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::BTreeMap;
+
+use leptos::prelude::*;
+use zhongchar_core::character::Character;
+use zhongchar_core::radical::Radical;
+
+use crate::helpers::{character_href, page_href, radical_href};
+
+fn lookup_href(number: i32) -> String {
+    page_href(&format!("lookup/{}", number))
+}
+
+/// The radical index of a paper dictionary: every radical, grouped under its
+/// stroke count, with the chosen one highlighted.
+pub(crate) fn radical_index_view(radicals: &[Radical], selected: Memo<Option<i32>>) -> impl IntoView {
+    let mut by_stroke_count: BTreeMap<i32, Vec<&Radical>> = BTreeMap::new();
+    for radical in radicals {
+        by_stroke_count.entry(radical.stroke_count).or_default().push(radical);
+    }
+    view! {
+        <div class="flex flex-col gap-2">
+            {by_stroke_count.into_iter().map(|(stroke_count, radicals)| view! {
+                <div class="flex gap-4 items-baseline">
+                    <span class="badge badge-neutral flex-none w-12">{stroke_count}</span>
+                    <div class="flex flex-wrap gap-1">
+                        {radicals.into_iter().map(|radical| {
+                            let number = radical.number;
+                            view! {
+                                <a class="btn btn-sm text-xl"
+                                    class=("btn-primary", move || selected.get() == Some(number))
+                                    class=("btn-ghost", move || selected.get() != Some(number))
+                                    href=lookup_href(number)
+                                    title=format!("{}. {}", number, radical.meaning)
+                                >
+                                    {radical.radical_forms.primary.to_string()}
+                                </a>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </div>
+                </div>
+            }).collect::<Vec<_>>()}
+        </div>
+    }
+}
+
+/// The character index for one radical: its characters grouped by the
+/// strokes left after the radical, each linking to the character's page.
+pub(crate) fn residual_index_view(radical: &Radical, dictionary: &[Character]) -> impl IntoView {
+    let characters_by_residual_strokes = radical.characters_by_residual_strokes(dictionary);
+    let forms = radical.radical_forms.all().map(|form| form.to_string()).collect::<Vec<_>>().join(" ");
+    view! {
+        <div class="flex flex-col gap-2">
+            <div class="flex items-baseline gap-3">
+                <span class="text-5xl leading-none">{forms}</span>
+                <a class="link link-hover opacity-70" href=radical_href(radical.number)>
+                    "Radical "{radical.number}", "{radical.meaning.clone()}
+                </a>
+            </div>
+            {characters_by_residual_strokes.is_empty().then(|| view! {
+                <p class="opacity-70">"No characters in the dictionary are filed under this radical."</p>
+            })}
+            {residual_strokes_view(characters_by_residual_strokes)}
+        </div>
+    }
+}
+
+/// One row per residual stroke count, as from
+/// `Radical::characters_by_residual_strokes`. Shared with the radical page.
+pub(crate) fn residual_strokes_view(characters_by_residual_strokes: BTreeMap<usize, Vec<Character>>) -> impl IntoView {
+    characters_by_residual_strokes.into_iter().map(|(residual, characters)| view! {
+        <div class="flex gap-4 items-baseline">
+            <span class="badge badge-neutral flex-none w-12">"+"{residual}</span>
+            <div class="flex flex-wrap gap-1">
+                {characters.into_iter().map(|character| view! {
+                    <a class="btn btn-ghost btn-sm text-xl"
+                        href=character_href(character.character)
+                        title=character.definition.clone().unwrap_or_default()
+                    >
+                        {character.character.to_string()}
+                    </a>
+                }).collect::<Vec<_>>()}
+            </div>
+        </div>
+    }).collect::<Vec<_>>()
+}
@@ -1,13 +1,31 @@
-mod model;
-
 mod app;
+mod component_strokes;
+mod data_export;
+mod datasets;
+mod flashcards;
 mod helpers;
+mod lookup;
+mod quiz;
+mod service_worker;
+mod storage;
+mod stroke_order;
+mod stroke_tracing;
 
 use app::*;
+use datasets::{DatasetProgress, FetchPolicy};
 use leptos::logging;
+use leptos::prelude::*;
+use service_worker::{register_service_worker, UpdateWaiting};
 
 fn main() {
     console_error_panic_hook::set_once();
     logging::log!("csr mode - mounting to body");
-    leptos::mount::mount_to_body(App)
+    let update_waiting = RwSignal::new(false);
+    register_service_worker(update_waiting);
+    leptos::mount::mount_to_body(move || {
+        provide_context(UpdateWaiting(update_waiting));
+        provide_context(FetchPolicy::default());
+        provide_context(DatasetProgress(RwSignal::new(Vec::new())));
+        view! { <App/> }
+    })
 }
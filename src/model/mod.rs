@@ -1,3 +0,0 @@
-pub mod error;
-mod radical_from_csv;
-pub mod radical;
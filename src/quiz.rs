@@ -0,0 +1,239 @@
+use leptos::prelude::*;
+use zhongchar_core::quiz::{AnswerFormat, Outcome, Question, QuestionKind, QuestionResult, QuizRng, QuizSession, QuizSummary};
+use zhongchar_core::radical::Radical;
+
+use crate::helpers::radical_href;
+
+const QUIZ_LENGTHS: [usize; 3] = [10, 20, 40];
+
+/// Quiz setup, questions one at a time with feedback after each, and a
+/// summary at the end.
+#[component]
+pub(crate) fn QuizRunner(radicals: Vec<Radical>) -> impl IntoView {
+    let radicals = StoredValue::new(radicals);
+    let kinds = RwSignal::new(QuestionKind::ALL.to_vec());
+    let length = RwSignal::new(QUIZ_LENGTHS[0]);
+    let session = RwSignal::new(None::<QuizSession>);
+    // Whether the last answer's feedback is showing.
+    let answered = RwSignal::new(false);
+    let typed = RwSignal::new(String::new());
+
+    let start = move || {
+        let mut rng = QuizRng::new(js_sys::Date::now() as u64);
+        let quiz = radicals.with_value(|radicals| {
+            kinds.with_untracked(|kinds| QuizSession::generate(radicals, kinds, length.get_untracked(), &mut rng))
+        });
+        session.set(Some(quiz));
+        answered.set(false);
+        typed.set(String::new());
+    };
+    let answer = move |response: String| {
+        session.update(|session| {
+            if let Some(session) = session {
+                session.answer(&response);
+            }
+        });
+        answered.set(true);
+    };
+    let next = move |_| {
+        answered.set(false);
+        typed.set(String::new());
+    };
+
+    let setup = move || view! {
+        <div class="flex flex-col gap-4 max-w-2xl">
+            <div class="flex flex-wrap gap-3">
+                {QuestionKind::ALL.into_iter().map(|kind| view! {
+                    <label class="label cursor-pointer gap-2">
+                        <input type="checkbox" class="checkbox checkbox-sm"
+                            prop:checked=move || kinds.with(|kinds| kinds.contains(&kind))
+                            on:change=move |ev| {
+                                let checked = event_target_checked(&ev);
+                                kinds.update(|kinds| {
+                                    kinds.retain(|other| *other != kind);
+                                    if checked {
+                                        kinds.push(kind);
+                                        kinds.sort();
+                                    }
+                                });
+                            }
+                        />
+                        <span>{kind.to_string()}</span>
+                    </label>
+                }).collect::<Vec<_>>()}
+            </div>
+            <div class="join">
+                {QUIZ_LENGTHS.into_iter().map(|option| view! {
+                    <button class="btn btn-sm join-item"
+                        class:btn-active=move || length.get() == option
+                        on:click=move |_| length.set(option)
+                    >
+                        {option}" questions"
+                    </button>
+                }).collect::<Vec<_>>()}
+            </div>
+            <button class="btn btn-primary w-fit"
+                disabled=move || kinds.with(|kinds| kinds.is_empty())
+                on:click=move |_| start()
+            >
+                "Start quiz"
+            </button>
+        </div>
+    };
+
+    let question_view = move |question: Question, position: usize, total: usize| {
+        let input = match question.format.clone() {
+            AnswerFormat::Choice { options, .. } => view! {
+                <div class="grid grid-cols-2 gap-2 w-fit">
+                    {options.into_iter().map(|option| {
+                        let label = option.clone();
+                        view! {
+                            <button class="btn btn-lg text-4xl h-20 w-24" on:click=move |_| answer(option.clone())>
+                                {label}
+                            </button>
+                        }
+                    }).collect::<Vec<_>>()}
+                </div>
+            }.into_any(),
+            AnswerFormat::Typed { .. } => view! {
+                <form class="join" on:submit=move |ev| {
+                    ev.prevent_default();
+                    answer(typed.get_untracked());
+                }>
+                    <input class="input join-item" type="text" autocomplete="off" autofocus
+                        placeholder="e.g. rén or ren2"
+                        bind:value=typed
+                    />
+                    <button class="btn btn-primary join-item" type="submit">"Check"</button>
+                </form>
+            }.into_any(),
+        };
+        view! {
+            <div class="flex flex-col gap-4">
+                <progress class="progress w-64" value=position.to_string() max=total.to_string()></progress>
+                {prompt_view(&question)}
+                {input}
+            </div>
+        }
+    };
+
+    let feedback_view = move |result: QuestionResult, finished: bool| {
+        let correct_answer = result.question.correct_answer();
+        let message = match result.outcome {
+            Outcome::Correct => "Correct!".to_string(),
+            Outcome::WrongTone => format!("Right syllable, wrong tone: it's {}.", correct_answer),
+            Outcome::Wrong => format!("Not quite: it's {}.", correct_answer),
+        };
+        let alert = if result.outcome == Outcome::Correct { "alert alert-success" } else { "alert alert-error" };
+        let options = match &result.question.format {
+            AnswerFormat::Choice { options, correct } => Some(view! {
+                <div class="grid grid-cols-2 gap-2 w-fit">
+                    {options.iter().enumerate().map(|(index, option)| {
+                        let class = if index == *correct {
+                            "btn btn-lg text-4xl h-20 w-24 btn-success"
+                        } else if *option == result.response {
+                            "btn btn-lg text-4xl h-20 w-24 btn-error"
+                        } else {
+                            "btn btn-lg text-4xl h-20 w-24 btn-disabled"
+                        };
+                        view! { <span class=class>{option.clone()}</span> }
+                    }).collect::<Vec<_>>()}
+                </div>
+            }),
+            AnswerFormat::Typed { .. } => None,
+        };
+        view! {
+            <div class="flex flex-col gap-4">
+                {prompt_view(&result.question)}
+                {options}
+                <div role="status" class=alert>{message}</div>
+                <button class="btn btn-primary w-fit" on:click=next>
+                    {if finished { "See results" } else { "Next" }}
+                </button>
+            </div>
+        }
+    };
+
+    move || {
+        let Some(state) = session.get() else { return setup().into_any() };
+        if state.is_empty() {
+            return view! {
+                <div class="flex flex-col gap-4">
+                    <p>"No questions could be made from the chosen kinds."</p>
+                    <button class="btn w-fit" on:click=move |_| session.set(None)>"Back"</button>
+                </div>
+            }.into_any();
+        }
+        if answered.get() && let Some(result) = state.last_result() {
+            return feedback_view(result.clone(), state.is_finished()).into_any();
+        }
+        match state.current() {
+            Some(question) => question_view(question.clone(), state.position(), state.len()).into_any(),
+            None => summary_view(state.summary(), move || start(), move || session.set(None)).into_any(),
+        }
+    }
+}
+
+fn prompt_view(question: &Question) -> impl IntoView {
+    let prompt_class = if question.kind == QuestionKind::PinyinWithTones { "text-9xl leading-none" } else { "text-3xl" };
+    view! {
+        <p class="opacity-70">{question.kind.instruction()}</p>
+        <p class=prompt_class>{question.prompt.clone()}</p>
+    }
+}
+
+fn summary_view(
+    summary: QuizSummary,
+    again: impl Fn() + Send + Sync + 'static,
+    change_settings: impl Fn() + Send + Sync + 'static,
+) -> impl IntoView {
+    view! {
+        <div class="flex flex-col gap-6 max-w-2xl">
+            <div class="stats shadow w-fit">
+                <div class="stat">
+                    <div class="stat-title">"Score"</div>
+                    <div class="stat-value">{summary.correct}" / "{summary.total}</div>
+                    <div class="stat-desc">{format!("{}% correct", (summary.accuracy() * 100.0).round())}</div>
+                </div>
+            </div>
+            <table class="table table-sm w-fit">
+                <tbody>
+                    {summary.by_kind.iter().map(|(kind, (correct, total))| view! {
+                        <tr>
+                            <td>{kind.to_string()}</td>
+                            <td>{*correct}" / "{*total}</td>
+                        </tr>
+                    }).collect::<Vec<_>>()}
+                </tbody>
+            </table>
+            {(!summary.missed.is_empty()).then(|| view! {
+                <section class="flex flex-col gap-2">
+                    <h2 class="text-lg font-bold">"To review"</h2>
+                    <table class="table table-sm w-fit">
+                        <thead>
+                            <tr><th>"Question"</th><th>"Your answer"</th><th>"Answer"</th><th></th></tr>
+                        </thead>
+                        <tbody>
+                            {summary.missed.iter().map(|result| view! {
+                                <tr>
+                                    <td>{result.question.prompt.clone()}</td>
+                                    <td>{result.response.clone()}</td>
+                                    <td>{result.question.correct_answer()}</td>
+                                    <td>
+                                        <a class="link link-hover" href=radical_href(result.question.radical)>
+                                            "Radical "{result.question.radical}
+                                        </a>
+                                    </td>
+                                </tr>
+                            }).collect::<Vec<_>>()}
+                        </tbody>
+                    </table>
+                </section>
+            })}
+            <div class="flex gap-2">
+                <button class="btn btn-primary" on:click=move |_| again()>"New quiz"</button>
+                <button class="btn" on:click=move |_| change_settings()>"Change settings"</button>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,81 @@
+use leptos::logging::log;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos::wasm_bindgen::closure::Closure;
+use leptos::wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{ServiceWorkerContainer, ServiceWorkerRegistration, ServiceWorkerState};
+
+/// Set once a new service worker has installed and is waiting to take over
+/// from the one serving the current page.
+#[derive(Clone, Copy)]
+pub(crate) struct UpdateWaiting(pub RwSignal<bool>);
+
+fn service_worker_container() -> Option<ServiceWorkerContainer> {
+    let navigator = window().navigator();
+    // `navigator.serviceWorker` is missing outside secure contexts.
+    let supported = js_sys::Reflect::has(&navigator, &JsValue::from_str("serviceWorker")).unwrap_or(false);
+    supported.then(|| navigator.service_worker())
+}
+
+pub(crate) fn register_service_worker(update_waiting: RwSignal<bool>) {
+    let Some(container) = service_worker_container() else {
+        log!("service workers are not supported here");
+        return;
+    };
+    let base_url = option_env!("BASE_URL").unwrap_or("/");
+    let script_url = format!("{}sw.js", base_url);
+
+    spawn_local(async move {
+        let registration = match JsFuture::from(container.register(&script_url)).await {
+            Ok(registration) => registration.unchecked_into::<ServiceWorkerRegistration>(),
+            Err(error) => {
+                log!("service worker registration failed: {:?}", error);
+                return;
+            }
+        };
+        // Without a controller this is the first install rather than an update.
+        let is_update = move || container.controller().is_some();
+        if registration.waiting().is_some() && is_update() {
+            update_waiting.set(true);
+        }
+
+        let installing_registration = registration.clone();
+        let on_update_found = Closure::<dyn FnMut()>::new(move || {
+            let Some(installing) = installing_registration.installing() else { return };
+            let is_update = is_update.clone();
+            let watched = installing.clone();
+            let on_state_change = Closure::<dyn FnMut()>::new(move || {
+                if watched.state() == ServiceWorkerState::Installed && is_update() {
+                    update_waiting.set(true);
+                }
+            });
+            installing.set_onstatechange(Some(on_state_change.as_ref().unchecked_ref()));
+            on_state_change.forget();
+        });
+        registration.set_onupdatefound(Some(on_update_found.as_ref().unchecked_ref()));
+        on_update_found.forget();
+    });
+}
+
+/// Tells the waiting service worker to activate and reloads once it has
+/// taken control of the page.
+pub(crate) fn apply_update() {
+    let Some(container) = service_worker_container() else { return };
+    let on_controller_change = Closure::<dyn FnMut()>::new(move || {
+        let _ = window().location().reload();
+    });
+    container.set_oncontrollerchange(Some(on_controller_change.as_ref().unchecked_ref()));
+    on_controller_change.forget();
+
+    spawn_local(async move {
+        let registration = match JsFuture::from(container.get_registration()).await {
+            Ok(registration) if !registration.is_undefined() => registration.unchecked_into::<ServiceWorkerRegistration>(),
+            _ => return,
+        };
+        let Some(waiting) = registration.waiting() else { return };
+        if let Err(error) = waiting.post_message(&JsValue::from_str("SKIP_WAITING")) {
+            log!("could not activate the new service worker: {:?}", error);
+        }
+    });
+}
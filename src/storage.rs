@@ -0,0 +1,433 @@
+use indexed_db_futures::database::Database;
+use indexed_db_futures::object_store::ObjectStore;
+use indexed_db_futures::prelude::*;
+use indexed_db_futures::transaction::TransactionMode;
+use leptos::logging::log;
+use leptos::task::spawn_local;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use zhongchar_core::backup::{Backup, CardRecord, RestoreMode, TracingRecord};
+use zhongchar_core::error::{ZhongCharError, ZhongCharResult};
+use zhongchar_core::flashcards::{CardId, ReviewRecord};
+use zhongchar_core::graphics::CharacterGraphics;
+use zhongchar_core::scheduler::CardState;
+use zhongchar_core::tracing::TracingStats;
+
+use crate::datasets::{fetch_dataset, Download, FetchPolicy, FetchProgress};
+use crate::helpers::{read_setting, remove_setting, write_setting, BACKED_UP_SETTINGS};
+
+const DATABASE_NAME: &str = "zhongchar";
+const DATABASE_VERSION: u32 = 1;
+const DATASETS_STORE: &str = "datasets";
+/// `CachedGraphics` keyed by character.
+const GRAPHICS_STORE: &str = "graphics";
+/// `TracingRecord`s keyed by the traced character.
+const TRACING_STORE: &str = "tracing";
+/// `CardRecord`s keyed by `CardId::key`.
+const CARDS_STORE: &str = "cards";
+/// `ReviewRecord`s keyed by `review_key`, so they list oldest first.
+const REVIEWS_STORE: &str = "reviews";
+
+/// Bump whenever the shape of a cached model type changes, so entries written
+/// by an older build are ignored instead of failing to deserialize.
+const DATASET_SCHEMA_VERSION: u32 = 3;
+
+#[derive(Serialize, Deserialize)]
+struct CachedDataset<D> {
+    schema_version: u32,
+    /// Hex FNV-1a hash of the source file. Kept as a string because
+    /// IndexedDB numbers can't hold a full u64.
+    content_hash: String,
+    /// The `ETag` the file was served with, so a refresh can skip the
+    /// download when it hasn't changed.
+    etag: Option<String>,
+    data: D,
+}
+
+pub(crate) async fn open_database() -> ZhongCharResult<Database> {
+    let database = Database::open(DATABASE_NAME)
+        .with_version(DATABASE_VERSION)
+        .with_on_upgrade_needed(|event, database| {
+            if event.old_version() < 1.0 {
+                for store in [DATASETS_STORE, GRAPHICS_STORE, TRACING_STORE, CARDS_STORE, REVIEWS_STORE] {
+                    database.create_object_store(store).build()?;
+                }
+            }
+            Ok(())
+        })
+        .await
+        .map_err(indexed_db_error)?;
+    Ok(database)
+}
+
+fn indexed_db_error(error: impl std::fmt::Display) -> ZhongCharError {
+    ZhongCharError::IndexedDb(error.to_string())
+}
+
+fn content_hash(text: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in text.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+async fn read_cached<T: DeserializeOwned>(file_name: &str) -> ZhongCharResult<Option<CachedDataset<Vec<T>>>> {
+    let database = open_database().await?;
+    let transaction = database.transaction(DATASETS_STORE).build().map_err(indexed_db_error)?;
+    let store = transaction.object_store(DATASETS_STORE).map_err(indexed_db_error)?;
+    let cached: Option<CachedDataset<Vec<T>>> = store.get(file_name)
+        .serde()
+        .map_err(indexed_db_error)?
+        .await
+        .map_err(indexed_db_error)?;
+    Ok(cached.filter(|cached| cached.schema_version == DATASET_SCHEMA_VERSION))
+}
+
+async fn write_cached<T: Serialize>(file_name: &str, download: &Download, data: &[T]) -> ZhongCharResult<()> {
+    let database = open_database().await?;
+    let transaction = database
+        .transaction(DATASETS_STORE)
+        .with_mode(TransactionMode::Readwrite)
+        .build()
+        .map_err(indexed_db_error)?;
+    let store = transaction.object_store(DATASETS_STORE).map_err(indexed_db_error)?;
+    let cached = CachedDataset {
+        schema_version: DATASET_SCHEMA_VERSION,
+        content_hash: content_hash(&download.text),
+        etag: download.etag.clone(),
+        data,
+    };
+    store.put(cached)
+        .with_key(file_name.to_string())
+        .serde()
+        .map_err(indexed_db_error)?
+        .await
+        .map_err(indexed_db_error)?;
+    transaction.commit().await.map_err(indexed_db_error)?;
+    Ok(())
+}
+
+/// Cache-first loading of a dataset from the site root.
+///
+/// A cached copy is returned straight away while a conditional request checks
+/// the source in the background; if it has changed the cache is rewritten and
+/// `on_refreshed` is called so the caller can reload. On a cache miss, or if
+/// IndexedDB is unavailable, the dataset is fetched and parsed directly, with
+/// `on_progress` following the download.
+pub(crate) async fn load_dataset<T>(
+    file_name: &'static str,
+    parse: fn(&str) -> ZhongCharResult<Vec<T>>,
+    policy: FetchPolicy,
+    on_progress: impl Fn(Option<FetchProgress>) + 'static,
+    on_refreshed: impl FnOnce() + 'static,
+) -> ZhongCharResult<Vec<T>>
+where
+    T: Serialize + DeserializeOwned + 'static,
+{
+    match read_cached::<T>(file_name).await {
+        Ok(Some(CachedDataset { content_hash: cached_hash, etag, data, .. })) => {
+            spawn_local(async move {
+                let refreshed = refresh_cached(file_name, parse, policy, &cached_hash, etag.as_deref(), on_refreshed);
+                if let Err(error) = refreshed.await {
+                    log!("could not refresh cached {}: {}", file_name, error);
+                }
+            });
+            return Ok(data);
+        }
+        Ok(None) => {}
+        Err(error) => log!("could not read cached {}: {}", file_name, error),
+    }
+
+    // Without an `ETag` to compare against the server can't answer 304.
+    let download = fetch_dataset(file_name, None, policy, on_progress).await?.unwrap_or_default();
+    let data = parse(&download.text)?;
+    if let Err(error) = write_cached(file_name, &download, &data).await {
+        log!("could not cache {}: {}", file_name, error);
+    }
+    Ok(data)
+}
+
+async fn refresh_cached<T: Serialize>(
+    file_name: &'static str,
+    parse: fn(&str) -> ZhongCharResult<Vec<T>>,
+    policy: FetchPolicy,
+    cached_hash: &str,
+    cached_etag: Option<&str>,
+    on_refreshed: impl FnOnce(),
+) -> ZhongCharResult<()> {
+    // The cached copy is already on screen, so this download stays silent.
+    // Without an `ETag` the file is downloaded again and compared by hash.
+    let Some(download) = fetch_dataset(file_name, cached_etag, policy, |_| {}).await? else { return Ok(()) };
+    let changed = content_hash(&download.text) != cached_hash;
+    if !changed && download.etag.as_deref() == cached_etag {
+        return Ok(());
+    }
+    // A new `ETag` for the same content is still stored, so the next check
+    // can be answered with a 304.
+    let data = parse(&download.text)?;
+    write_cached(file_name, &download, &data).await?;
+    if changed {
+        on_refreshed();
+    }
+    Ok(())
+}
+
+/// One character's stroke graphics, with the `ETag` they were served with.
+#[derive(Serialize, Deserialize)]
+struct CachedGraphics {
+    schema_version: u32,
+    etag: Option<String>,
+    graphics: CharacterGraphics,
+}
+
+/// Cache-first loading of one character's stroke graphics, or `None` if the
+/// site has no graphics for it.
+///
+/// A cached copy is returned straight away. If the server gave it an `ETag`,
+/// a conditional request checks for a newer file in the background and
+/// `on_refreshed` is called if one replaced it; otherwise nothing is fetched.
+pub(crate) async fn load_character_graphics(
+    character: char,
+    policy: FetchPolicy,
+    on_progress: impl Fn(Option<FetchProgress>) + 'static,
+    on_refreshed: impl FnOnce() + 'static,
+) -> ZhongCharResult<Option<CharacterGraphics>> {
+    let key = character.to_string();
+    let file_name = CharacterGraphics::file_name(character);
+    match read_record::<CachedGraphics>(GRAPHICS_STORE, &key).await {
+        Ok(Some(cached)) if cached.schema_version == DATASET_SCHEMA_VERSION => {
+            let graphics = cached.graphics.clone();
+            if let Some(etag) = cached.etag.clone() {
+                spawn_local(async move {
+                    if let Err(error) = refresh_graphics(cached, &etag, policy, on_refreshed).await {
+                        log!("could not refresh cached graphics for {}: {}", character, error);
+                    }
+                });
+            }
+            return Ok(Some(graphics));
+        }
+        Ok(_) => {}
+        Err(error) => log!("could not read cached graphics for {}: {}", character, error),
+    }
+
+    let download = match fetch_dataset(&file_name, None, policy, on_progress).await {
+        Err(ZhongCharError::Http { status: 404, .. }) => return Ok(None),
+        result => result?,
+    };
+    let Some(Download { text, etag }) = download else { return Ok(None) };
+    let graphics = CharacterGraphics::parse_character_graphics(&text)?;
+    let cached = CachedGraphics { schema_version: DATASET_SCHEMA_VERSION, etag, graphics: graphics.clone() };
+    if let Err(error) = write_record(GRAPHICS_STORE, key, cached).await {
+        log!("could not cache {}: {}", file_name, error);
+    }
+    Ok(Some(graphics))
+}
+
+async fn refresh_graphics(
+    cached: CachedGraphics,
+    etag: &str,
+    policy: FetchPolicy,
+    on_refreshed: impl FnOnce(),
+) -> ZhongCharResult<()> {
+    let character = cached.graphics.character;
+    // The cached copy is already on screen, so this check stays silent.
+    let download = fetch_dataset(&CharacterGraphics::file_name(character), Some(etag), policy, |_| {}).await?;
+    let Some(Download { text, etag }) = download else { return Ok(()) };
+    let graphics = CharacterGraphics::parse_character_graphics(&text)?;
+    let changed = graphics != cached.graphics;
+    let refreshed = CachedGraphics { schema_version: DATASET_SCHEMA_VERSION, etag, graphics };
+    write_record(GRAPHICS_STORE, character.to_string(), refreshed).await?;
+    if changed {
+        on_refreshed();
+    }
+    Ok(())
+}
+
+pub(crate) async fn read_tracing_stats(character: char) -> ZhongCharResult<TracingStats> {
+    let database = open_database().await?;
+    let transaction = database.transaction(TRACING_STORE).build().map_err(indexed_db_error)?;
+    let store = transaction.object_store(TRACING_STORE).map_err(indexed_db_error)?;
+    let record: Option<TracingRecord> = store.get(character.to_string())
+        .serde()
+        .map_err(indexed_db_error)?
+        .await
+        .map_err(indexed_db_error)?;
+    Ok(record.map(|record| record.stats).unwrap_or_default())
+}
+
+/// Adds a finished tracing of `character` to its running stats and returns
+/// the updated totals.
+pub(crate) async fn record_tracing(character: char, accuracy: f64) -> ZhongCharResult<TracingStats> {
+    let database = open_database().await?;
+    let transaction = database
+        .transaction(TRACING_STORE)
+        .with_mode(TransactionMode::Readwrite)
+        .build()
+        .map_err(indexed_db_error)?;
+    let store = transaction.object_store(TRACING_STORE).map_err(indexed_db_error)?;
+    let key = character.to_string();
+    let mut record: TracingRecord = store.get(key.clone())
+        .serde()
+        .map_err(indexed_db_error)?
+        .await
+        .map_err(indexed_db_error)?
+        .unwrap_or(TracingRecord { character, stats: TracingStats::default() });
+    record.stats.record(accuracy);
+    store.put(record)
+        .with_key(key)
+        .serde()
+        .map_err(indexed_db_error)?
+        .await
+        .map_err(indexed_db_error)?;
+    transaction.commit().await.map_err(indexed_db_error)?;
+    Ok(record.stats)
+}
+
+/// Review time zero-padded ahead of the card, so keys sort chronologically.
+fn review_key(review: &ReviewRecord) -> String {
+    format!("{:015.0}/{}", review.reviewed_at, review.card.key())
+}
+
+async fn read_all<T: DeserializeOwned>(store_name: &str) -> ZhongCharResult<Vec<T>> {
+    let database = open_database().await?;
+    let transaction = database.transaction(store_name).build().map_err(indexed_db_error)?;
+    let store = transaction.object_store(store_name).map_err(indexed_db_error)?;
+    let records = store.get_all()
+        .serde()
+        .map_err(indexed_db_error)?
+        .await
+        .map_err(indexed_db_error)?
+        .collect::<Result<Vec<T>, _>>()
+        .map_err(indexed_db_error)?;
+    Ok(records)
+}
+
+async fn read_record<T: DeserializeOwned>(store_name: &str, key: &str) -> ZhongCharResult<Option<T>> {
+    let database = open_database().await?;
+    let transaction = database.transaction(store_name).build().map_err(indexed_db_error)?;
+    let store = transaction.object_store(store_name).map_err(indexed_db_error)?;
+    let record = store.get(key)
+        .serde()
+        .map_err(indexed_db_error)?
+        .await
+        .map_err(indexed_db_error)?;
+    Ok(record)
+}
+
+async fn write_record<T: Serialize>(store_name: &str, key: String, record: T) -> ZhongCharResult<()> {
+    let database = open_database().await?;
+    let transaction = database
+        .transaction(store_name)
+        .with_mode(TransactionMode::Readwrite)
+        .build()
+        .map_err(indexed_db_error)?;
+    let store = transaction.object_store(store_name).map_err(indexed_db_error)?;
+    store.put(record)
+        .with_key(key)
+        .serde()
+        .map_err(indexed_db_error)?
+        .await
+        .map_err(indexed_db_error)?;
+    transaction.commit().await.map_err(indexed_db_error)?;
+    Ok(())
+}
+
+/// Scheduling state of every flashcard reviewed so far.
+pub(crate) async fn read_card_states() -> ZhongCharResult<BTreeMap<CardId, CardState>> {
+    let records: Vec<CardRecord> = read_all(CARDS_STORE).await?;
+    Ok(records.into_iter().map(|record| (record.card, record.state)).collect())
+}
+
+/// Every flashcard answer, oldest first.
+pub(crate) async fn read_review_history() -> ZhongCharResult<Vec<ReviewRecord>> {
+    read_all(REVIEWS_STORE).await
+}
+
+/// Saves a review to the history along with the card state it led to, in one
+/// transaction so neither is stored without the other.
+pub(crate) async fn record_review(review: ReviewRecord, state: CardState) -> ZhongCharResult<()> {
+    let database = open_database().await?;
+    let transaction = database
+        .transaction([REVIEWS_STORE, CARDS_STORE])
+        .with_mode(TransactionMode::Readwrite)
+        .build()
+        .map_err(indexed_db_error)?;
+    let reviews = transaction.object_store(REVIEWS_STORE).map_err(indexed_db_error)?;
+    reviews.put(review)
+        .with_key(review_key(&review))
+        .serde()
+        .map_err(indexed_db_error)?
+        .await
+        .map_err(indexed_db_error)?;
+    let cards = transaction.object_store(CARDS_STORE).map_err(indexed_db_error)?;
+    cards.put(CardRecord { card: review.card, state })
+        .with_key(review.card.key())
+        .serde()
+        .map_err(indexed_db_error)?
+        .await
+        .map_err(indexed_db_error)?;
+    transaction.commit().await.map_err(indexed_db_error)?;
+    Ok(())
+}
+
+/// Everything the learner has recorded, ready to download as a backup.
+pub(crate) async fn read_backup() -> ZhongCharResult<Backup> {
+    let cards = read_all(CARDS_STORE).await?;
+    let reviews = read_all(REVIEWS_STORE).await?;
+    let tracing = read_all(TRACING_STORE).await?;
+    let settings = BACKED_UP_SETTINGS.into_iter()
+        .filter_map(|key| Some((key.to_string(), read_setting(key)?)))
+        .collect();
+    Ok(Backup::new(js_sys::Date::now(), cards, reviews, tracing, settings))
+}
+
+/// Restores a backup over the stored data and returns what is now stored.
+/// Everything is rewritten in one transaction, so a failed restore leaves the
+/// old data in place.
+pub(crate) async fn restore_backup(backup: Backup, mode: RestoreMode) -> ZhongCharResult<Backup> {
+    let restored = read_backup().await?.restore(backup, mode);
+    let database = open_database().await?;
+    let transaction = database
+        .transaction([CARDS_STORE, REVIEWS_STORE, TRACING_STORE])
+        .with_mode(TransactionMode::Readwrite)
+        .build()
+        .map_err(indexed_db_error)?;
+    let cards = transaction.object_store(CARDS_STORE).map_err(indexed_db_error)?;
+    replace_all(&cards, restored.cards.iter().map(|record| (record.card.key(), *record))).await?;
+    let reviews = transaction.object_store(REVIEWS_STORE).map_err(indexed_db_error)?;
+    replace_all(&reviews, restored.reviews.iter().map(|review| (review_key(review), *review))).await?;
+    let tracing = transaction.object_store(TRACING_STORE).map_err(indexed_db_error)?;
+    replace_all(&tracing, restored.tracing.iter().map(|record| (record.character.to_string(), *record))).await?;
+    transaction.commit().await.map_err(indexed_db_error)?;
+
+    for key in BACKED_UP_SETTINGS {
+        match restored.settings.get(key) {
+            Some(value) => write_setting(key, value),
+            None => remove_setting(key),
+        }
+    }
+    Ok(restored)
+}
+
+async fn replace_all<T: Serialize>(
+    store: &ObjectStore<'_>,
+    records: impl IntoIterator<Item = (String, T)>,
+) -> ZhongCharResult<()> {
+    store.clear()
+        .map_err(indexed_db_error)?
+        .await
+        .map_err(indexed_db_error)?;
+    for (key, record) in records {
+        store.put(record)
+            .with_key(key)
+            .serde()
+            .map_err(indexed_db_error)?
+            .await
+            .map_err(indexed_db_error)?;
+    }
+    Ok(())
+}
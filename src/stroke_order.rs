@@ -0,0 +1,158 @@
+use leptos::prelude::*;
+use leptos_use::use_interval_fn;
+use zhongchar_core::graphics::CharacterGraphics;
+
+use crate::app::{LoadingStatus, PageGraphics};
+use crate::stroke_tracing::StrokeTracing;
+
+/// Time between strokes while playing; the drawing animation itself is
+/// `.stroke-draw` in the stylesheet and should finish within this.
+const STROKE_INTERVAL_MS: u64 = 800;
+
+/// Stroke order for the character page's character, from the graphics the
+/// page provides as `PageGraphics`. The rest of the page doesn't depend on
+/// them, so failures stay local.
+#[component]
+pub(crate) fn StrokeOrderSection() -> impl IntoView {
+    let PageGraphics(graphics_fetched) = expect_context::<PageGraphics>();
+    view! {
+        <section class="flex flex-col gap-2">
+            <h2 class="text-lg font-bold">"Stroke Order"</h2>
+            <Suspense fallback=move || view! {
+                <div class="skeleton w-48 h-48"></div>
+                <span class="opacity-70"><LoadingStatus/></span>
+            }>
+                <ErrorBoundary fallback=|errors| view! {
+                    <p class="opacity-60" title=move || errors.get().into_iter().map(|(_, e)| e.to_string()).collect::<Vec<_>>().join("\n")>
+                        "Stroke data is unavailable."
+                    </p>
+                }>
+                    {move || Suspend::new(async move {
+                        graphics_fetched.await.map(|graphics| {
+                            match graphics {
+                                Some(graphics) => view! {
+                                    <div class="flex flex-wrap gap-6">
+                                        <StrokeOrder graphics=graphics.clone()/>
+                                        <div class="flex flex-col gap-2">
+                                            <h3 class="font-bold">"Trace It"</h3>
+                                            <StrokeTracing graphics=graphics/>
+                                        </div>
+                                    </div>
+                                }.into_any(),
+                                None => view! {
+                                    <p class="opacity-60">"No stroke data for this character."</p>
+                                }.into_any(),
+                            }
+                        })
+                    })}
+                </ErrorBoundary>
+            </Suspense>
+        </section>
+    }
+}
+
+/// Draws a character stroke by stroke with play, pause and step controls.
+///
+/// Each new stroke is revealed by animating a thick line along its median,
+/// clipped to the stroke's outline, so it fills in the direction it's written.
+#[component]
+pub(crate) fn StrokeOrder(graphics: CharacterGraphics) -> impl IntoView {
+    let stroke_count = graphics.stroke_count();
+    // Strokes drawn so far; the character starts out complete.
+    let shown = RwSignal::new(stroke_count);
+    let playing = RwSignal::new(false);
+    // Only strokes added by play or step animate, not the initial drawing.
+    let animate_latest = RwSignal::new(false);
+    let graphics = StoredValue::new(graphics);
+
+    let _ = use_interval_fn(
+        move || {
+            if !playing.get_untracked() {
+                return;
+            }
+            let next = (shown.get_untracked() + 1).min(stroke_count);
+            shown.set(next);
+            if next == stroke_count {
+                playing.set(false);
+            }
+        },
+        STROKE_INTERVAL_MS,
+    );
+
+    let play = move |_| {
+        if shown.get_untracked() == stroke_count {
+            shown.set(0);
+        }
+        animate_latest.set(true);
+        playing.set(true);
+    };
+    let pause = move |_| playing.set(false);
+    let step = move |_| {
+        playing.set(false);
+        animate_latest.set(true);
+        shown.update(|shown| *shown = if *shown >= stroke_count { 1 } else { *shown + 1 });
+    };
+
+    let outlines = graphics.with_value(|graphics| {
+        graphics.strokes.iter()
+            .map(|stroke| view! { <path d=stroke.clone() class="fill-base-300"/> })
+            .collect::<Vec<_>>()
+    });
+    let drawn = move || {
+        let shown = shown.get();
+        let animate_latest = animate_latest.get_untracked();
+        graphics.with_value(|graphics| {
+            graphics.strokes.iter()
+                .take(shown)
+                .enumerate()
+                .map(|(index, stroke)| {
+                    let median = graphics.median_path(index).filter(|_| animate_latest && index + 1 == shown);
+                    match median {
+                        Some(median) => {
+                            let clip_id = format!("stroke-clip-{:x}", graphics.character as u32);
+                            view! {
+                                <clipPath id=clip_id.clone()>
+                                    <path d=stroke.clone()/>
+                                </clipPath>
+                                <path
+                                    d=median
+                                    class="stroke-draw stroke-base-content"
+                                    pathLength="1"
+                                    fill="none"
+                                    stroke-width="128"
+                                    stroke-linecap="round"
+                                    stroke-linejoin="round"
+                                    clip-path=format!("url(#{})", clip_id)
+                                />
+                            }.into_any()
+                        }
+                        None => view! { <path d=stroke.clone() class="fill-base-content"/> }.into_any(),
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+
+    view! {
+        <div class="flex flex-col gap-2 w-fit">
+            <svg viewBox=CharacterGraphics::SVG_VIEW_BOX class="w-48 h-48 bg-base-200 rounded-box">
+                <g transform=CharacterGraphics::SVG_TRANSFORM>
+                    {outlines}
+                    {drawn}
+                </g>
+            </svg>
+            <div class="flex items-center gap-2">
+                <div class="join">
+                    <Show
+                        when=move || playing.get()
+                        fallback=move || view! { <button class="btn btn-sm join-item" on:click=play>"Play"</button> }
+                    >
+                        <button class="btn btn-sm join-item" on:click=pause>"Pause"</button>
+                    </Show>
+                    <button class="btn btn-sm join-item" on:click=step>"Step"</button>
+                </div>
+                <span class="opacity-70">{move || shown.get()}" / "{stroke_count}</span>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,203 @@
+use leptos::html::Canvas;
+use leptos::logging::log;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos::wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, Path2d, PointerEvent};
+use zhongchar_core::graphics::CharacterGraphics;
+use zhongchar_core::tracing::{Point, StrokeGrade, TracingQuiz, TracingStats};
+
+use crate::storage::{read_tracing_stats, record_tracing};
+
+/// Backing resolution of the canvas; it's displayed at a smaller CSS size.
+const CANVAS_SIZE: u32 = 512;
+/// Width of the learner's ink, in graphics units.
+const INK_WIDTH: f64 = 48.0;
+
+/// Practice mode: the learner traces each stroke on a blank canvas and every
+/// attempt is graded against the reference median. The result of each full
+/// pass is added to the character's stats in IndexedDB.
+#[component]
+pub(crate) fn StrokeTracing(graphics: CharacterGraphics) -> impl IntoView {
+    let character = graphics.character;
+    let quiz = RwSignal::new(TracingQuiz::new(&graphics));
+    let graphics = StoredValue::new(graphics);
+    let ink = RwSignal::new(Vec::<Point>::new());
+    let drawing = RwSignal::new(false);
+    let last_grade = RwSignal::new(None::<StrokeGrade>);
+    let stats = RwSignal::new(None::<TracingStats>);
+    let canvas = NodeRef::<Canvas>::new();
+
+    spawn_local(async move {
+        match read_tracing_stats(character).await {
+            Ok(saved) => stats.set(Some(saved)),
+            Err(error) => log!("could not read tracing stats for {}: {}", character, error),
+        }
+    });
+
+    Effect::new(move |_| {
+        let Some(canvas) = canvas.get() else { return };
+        let result = quiz.with(|quiz| {
+            ink.with(|ink| graphics.with_value(|graphics| draw(&canvas, graphics, quiz, ink)))
+        });
+        if let Err(error) = result {
+            log!("could not draw the tracing canvas: {:?}", error);
+        }
+    });
+
+    let on_pointer_down = move |ev: PointerEvent| {
+        if quiz.with_untracked(TracingQuiz::is_complete) {
+            return;
+        }
+        let Some(canvas) = canvas.get_untracked() else { return };
+        let _ = canvas.set_pointer_capture(ev.pointer_id());
+        drawing.set(true);
+        ink.set(vec![to_graphics(&ev, &canvas)]);
+    };
+    let on_pointer_move = move |ev: PointerEvent| {
+        if !drawing.get_untracked() {
+            return;
+        }
+        let Some(canvas) = canvas.get_untracked() else { return };
+        ink.update(|ink| ink.push(to_graphics(&ev, &canvas)));
+    };
+    let on_pointer_up = move |_: PointerEvent| {
+        if !drawing.get_untracked() {
+            return;
+        }
+        drawing.set(false);
+        let tracing = ink.get_untracked();
+        ink.set(Vec::new());
+        let Some(grade) = quiz.try_update(|quiz| quiz.submit(&tracing)).flatten() else { return };
+        last_grade.set(Some(grade));
+        let (complete, accuracy) = quiz.with_untracked(|quiz| (quiz.is_complete(), quiz.accuracy()));
+        if complete {
+            spawn_local(async move {
+                match record_tracing(character, accuracy).await {
+                    Ok(updated) => stats.set(Some(updated)),
+                    Err(error) => log!("could not record tracing of {}: {}", character, error),
+                }
+            });
+        }
+    };
+    let start_over = move |_| {
+        quiz.set(graphics.with_value(TracingQuiz::new));
+        last_grade.set(None);
+    };
+
+    let feedback = move || {
+        quiz.with(|quiz| {
+            if quiz.is_complete() {
+                return format!(
+                    "Done: {}% of strokes right first time, {} {}.",
+                    (quiz.accuracy() * 100.0).round(),
+                    quiz.mistakes(),
+                    if quiz.mistakes() == 1 { "mistake" } else { "mistakes" },
+                );
+            }
+            let next = quiz.current_stroke().unwrap_or_default() + 1;
+            let mut message = match last_grade.get() {
+                None => format!("Trace stroke {} of {}.", next, quiz.stroke_count()),
+                Some(StrokeGrade::Correct) => format!("Good. Now stroke {}.", next),
+                Some(StrokeGrade::WrongDirection) => "Right stroke, wrong direction.".to_string(),
+                Some(StrokeGrade::WrongOrder { stroke }) => {
+                    format!("That's stroke {}; stroke {} comes first.", stroke + 1, next)
+                }
+                Some(StrokeGrade::WrongShape) => format!("That doesn't match stroke {}.", next),
+            };
+            if quiz.hint_due() {
+                message.push_str(" Follow the highlighted stroke from the dot.");
+            }
+            message
+        })
+    };
+    let stats_summary = move || {
+        stats.get().filter(|stats| stats.attempts > 0).map(|stats| {
+            format!(
+                "Average {}% over {} {}, best {}%",
+                (stats.average_accuracy() * 100.0).round(),
+                stats.attempts,
+                if stats.attempts == 1 { "attempt" } else { "attempts" },
+                (stats.best_accuracy * 100.0).round(),
+            )
+        })
+    };
+
+    view! {
+        <div class="flex flex-col gap-2 w-64">
+            <canvas
+                node_ref=canvas
+                width=CANVAS_SIZE.to_string()
+                height=CANVAS_SIZE.to_string()
+                class="w-64 h-64 bg-base-200 rounded-box touch-none cursor-crosshair"
+                on:pointerdown=on_pointer_down
+                on:pointermove=on_pointer_move
+                on:pointerup=on_pointer_up
+                on:pointercancel=on_pointer_up
+            ></canvas>
+            <p role="status">{feedback}</p>
+            <div class="flex items-center gap-2">
+                <button class="btn btn-sm" on:click=start_over>"Start over"</button>
+                <span class="text-sm opacity-70">{stats_summary}</span>
+            </div>
+        </div>
+    }
+}
+
+/// Pointer position in graphics coordinates.
+fn to_graphics(ev: &PointerEvent, canvas: &HtmlCanvasElement) -> Point {
+    let scale = 1024.0 / canvas.client_width().max(1) as f64;
+    (ev.offset_x() as f64 * scale, 900.0 - ev.offset_y() as f64 * scale)
+}
+
+fn draw(canvas: &HtmlCanvasElement, graphics: &CharacterGraphics, quiz: &TracingQuiz, ink: &[Point]) -> Result<(), JsValue> {
+    let context: CanvasRenderingContext2d = canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("no 2d context"))?
+        .dyn_into()?;
+    let size = canvas.width() as f64;
+    let ink_color = window().get_computed_style(canvas)?
+        .map(|style| style.get_property_value("color"))
+        .transpose()?
+        .unwrap_or_else(|| "black".to_string());
+
+    context.set_transform(1.0, 0.0, 0.0, 1.0, 0.0, 0.0)?;
+    context.clear_rect(0.0, 0.0, size, size);
+    // Same flip as CharacterGraphics::SVG_TRANSFORM, scaled to the canvas.
+    let scale = size / 1024.0;
+    context.set_transform(scale, 0.0, 0.0, -scale, 0.0, 900.0 * scale)?;
+    context.set_fill_style_str(&ink_color);
+    context.set_stroke_style_str(&ink_color);
+    context.set_line_cap("round");
+    context.set_line_join("round");
+
+    let done = quiz.current_stroke().unwrap_or(quiz.stroke_count());
+    for stroke in graphics.strokes.iter().take(done) {
+        context.fill_with_path_2d(&Path2d::new_with_path_string(stroke)?);
+    }
+
+    if let Some(current) = quiz.current_stroke().filter(|_| quiz.hint_due()) {
+        context.set_global_alpha(0.25);
+        if let Some(stroke) = graphics.strokes.get(current) {
+            context.fill_with_path_2d(&Path2d::new_with_path_string(stroke)?);
+        }
+        if let Some(&(x, y)) = graphics.medians.get(current).and_then(|median| median.first()) {
+            context.set_global_alpha(0.8);
+            context.begin_path();
+            context.arc(x as f64, y as f64, 36.0, 0.0, std::f64::consts::TAU)?;
+            context.fill();
+        }
+        context.set_global_alpha(1.0);
+    }
+
+    if let Some(&(x, y)) = ink.first() {
+        context.set_line_width(INK_WIDTH);
+        context.begin_path();
+        context.move_to(x, y);
+        for &(x, y) in &ink[1..] {
+            context.line_to(x, y);
+        }
+        context.stroke();
+    }
+    Ok(())
+}
@@ -0,0 +1,121 @@
+// Offline support for 中 Char.
+//
+// BUILD_VERSION is replaced at deploy time (see gh-pages-deploy.yml) so each
+// release installs as a new worker and gets its own cache.
+const BUILD_VERSION = "__BUILD_VERSION__";
+const CACHE_NAME = `zhongchar-${BUILD_VERSION}`;
+
+const APP_SHELL = [
+  "./",
+  "index.html",
+  "manifest.json",
+  "icon-256.png",
+];
+
+const DATASETS = [
+  "radicals.csv",
+  "dictionary.txt",
+];
+
+// One file per character, too many to download on install; each is cached
+// the first time a page asks for it.
+const LAZY_DATASET_DIRECTORY = "graphics/";
+
+const scoped = (path) => new URL(path, self.registration.scope).href;
+
+// Trunk fingerprints the wasm, js and css bundles and the favicon, so their
+// names are read out of the freshly fetched index.html rather than hard-coded.
+async function bundleUrls(indexResponse) {
+  const html = await indexResponse.clone().text();
+  const hrefs = [...html.matchAll(/(?:href|src)="([^"]+\.(?:wasm|js|css|ico))"/g)].map((match) => match[1]);
+  return hrefs.map((href) => new URL(href, self.registration.scope).href);
+}
+
+self.addEventListener("install", (event) => {
+  event.waitUntil((async () => {
+    const cache = await caches.open(CACHE_NAME);
+    const indexResponse = await fetch(scoped("index.html"), { cache: "no-cache" });
+    await cache.put(scoped("index.html"), indexResponse.clone());
+    const urls = [...APP_SHELL, ...DATASETS].map(scoped).concat(await bundleUrls(indexResponse));
+    await cache.addAll(urls.filter((url) => url !== scoped("index.html")));
+  })());
+});
+
+self.addEventListener("activate", (event) => {
+  event.waitUntil((async () => {
+    const names = await caches.keys();
+    await Promise.all(
+      names
+        .filter((name) => name.startsWith("zhongchar-") && name !== CACHE_NAME)
+        .map((name) => caches.delete(name)),
+    );
+    await self.clients.claim();
+  })());
+});
+
+// The page asks the waiting worker to take over once the user accepts the
+// update prompt.
+self.addEventListener("message", (event) => {
+  if (event.data === "SKIP_WAITING") {
+    self.skipWaiting();
+  }
+});
+
+self.addEventListener("fetch", (event) => {
+  const request = event.request;
+  if (request.method !== "GET" || !request.url.startsWith(self.registration.scope)) {
+    return;
+  }
+
+  // Client-side routes such as /char/水 all render from index.html. Static
+  // hosts answer them with a 404, so any failed navigation gets the shell.
+  if (request.mode === "navigate") {
+    event.respondWith((async () => {
+      try {
+        const response = await fetch(request);
+        if (response.ok) {
+          return response;
+        }
+        return (await caches.match(scoped("index.html"))) ?? response;
+      } catch (error) {
+        const cached = await caches.match(scoped("index.html"));
+        return cached ?? Response.error();
+      }
+    })());
+    return;
+  }
+
+  // Datasets are network first so the IndexedDB cache can notice edits,
+  // falling back to the precached copy offline.
+  const isDataset = DATASETS.some((dataset) => request.url === scoped(dataset))
+    || request.url.startsWith(scoped(LAZY_DATASET_DIRECTORY));
+  if (isDataset) {
+    event.respondWith((async () => {
+      const cache = await caches.open(CACHE_NAME);
+      try {
+        const response = await fetch(request);
+        if (response.ok) {
+          await cache.put(request, response.clone());
+        }
+        return response;
+      } catch (error) {
+        const cached = await cache.match(request);
+        return cached ?? Response.error();
+      }
+    })());
+    return;
+  }
+
+  event.respondWith((async () => {
+    const cached = await caches.match(request);
+    if (cached) {
+      return cached;
+    }
+    const response = await fetch(request);
+    if (response.ok) {
+      const cache = await caches.open(CACHE_NAME);
+      await cache.put(request, response.clone());
+    }
+    return response;
+  })());
+});
@@ -0,0 +1,365 @@
+//! Command-line access to the radicals and dictionary datasets the web app
+//! serves, for scripting.
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::{env, fs, process};
+
+use anyhow::{Context, Result, anyhow, bail};
+use zhongchar_core::character::{Character, Etymology};
+use zhongchar_core::graphics::CharacterGraphics;
+use zhongchar_core::ids::Decomposition;
+use zhongchar_core::radical::Radical;
+use zhongchar_core::script::ScriptMap;
+use zhongchar_core::validation::{DatasetReport, Severity};
+
+const USAGE: &str = "\
+usage: zhongchar [--json] [--data-dir <dir>] <command>
+
+commands:
+  radical <number|glyph>   show a Kangxi radical
+  char <glyph>             show a dictionary entry
+  search <query>           find radicals and characters by meaning or reading
+  decompose <glyph>        show a character's component tree
+  check                    report inconsistencies in radicals.csv
+  split-graphics <file>    split Make Me a Hanzi's graphics.txt into
+                           <dir>/graphics/, one file per character
+
+Datasets are read from <dir>/radicals.csv and <dir>/dictionary.txt. The
+directory defaults to $ZHONGCHAR_DATA_DIR, then ./public.";
+
+struct Options {
+    json: bool,
+    data_dir: PathBuf,
+    command: String,
+    argument: Option<String>,
+}
+
+impl Options {
+    fn argument(&self) -> Result<&str> {
+        self.argument.as_deref()
+            .ok_or_else(|| anyhow!("{} needs an argument\n\n{}", self.command, USAGE))
+    }
+}
+
+/// Parses the arguments after the program name. `data_dir` is the directory
+/// to use unless `--data-dir` is given, from `$ZHONGCHAR_DATA_DIR`.
+fn parse_options(args: impl IntoIterator<Item = String>, mut data_dir: Option<PathBuf>) -> Result<Options> {
+    let mut json = false;
+    let mut positional = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--json" => json = true,
+            "--data-dir" => {
+                let dir = args.next().ok_or_else(|| anyhow!("--data-dir needs a directory"))?;
+                data_dir = Some(PathBuf::from(dir));
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            _ => positional.push(arg),
+        }
+    }
+    let mut positional = positional.into_iter();
+    let command = positional.next().ok_or_else(|| anyhow!("expected a command\n\n{}", USAGE))?;
+    let argument = positional.next();
+    if positional.next().is_some() {
+        bail!("expected at most one argument\n\n{}", USAGE);
+    }
+    Ok(Options {
+        json,
+        data_dir: data_dir.unwrap_or_else(|| PathBuf::from("public")),
+        command,
+        argument,
+    })
+}
+
+fn load_radicals(data_dir: &Path) -> Result<Vec<Radical>> {
+    let path = data_dir.join(Radical::DATASET);
+    let text = fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+    Ok(Radical::parse_radicals(&text)?)
+}
+
+fn load_characters(data_dir: &Path) -> Result<Vec<Character>> {
+    let path = data_dir.join(Character::DATASET);
+    let text = fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+    Ok(Character::parse_dictionary(&text)?)
+}
+
+fn single_glyph(argument: &str) -> Result<char> {
+    let mut chars = argument.chars();
+    match (chars.next(), chars.next()) {
+        (Some(glyph), None) => Ok(glyph),
+        _ => bail!("expected a single character, got {:?}", argument),
+    }
+}
+
+fn print_radical(out: &mut impl Write, radical: &Radical) -> io::Result<()> {
+    writeln!(out, "{} {}", radical.number, radical.radical_forms)?;
+    writeln!(out, "  strokes      {}", radical.stroke_count)?;
+    writeln!(out, "  meaning      {}", radical.meaning)?;
+    if let Some(colloquial_term) = &radical.colloquial_term {
+        writeln!(out, "  colloquial   {}", colloquial_term)?;
+    }
+    writeln!(out, "  pinyin       {}", radical.pinyin.trim())?;
+    writeln!(out, "  hán-việt     {}", radical.han_viet)?;
+    writeln!(out, "  kana         {}", radical.hiragana_romaji)?;
+    writeln!(out, "  hangul       {}", radical.hangul_romaja)?;
+    writeln!(out, "  frequency    {}", radical.frequency)?;
+    if let Some(simplified) = &radical.simplified {
+        writeln!(out, "  simplified   {}", simplified)?;
+    }
+    writeln!(out, "  examples     {}", radical.examples_raw)?;
+    Ok(())
+}
+
+fn print_character(out: &mut impl Write, character: &Character, script_map: &ScriptMap) -> io::Result<()> {
+    writeln!(out, "{} {}", character.character, character.pinyin.join(", "))?;
+    if let Some(definition) = &character.definition {
+        writeln!(out, "  definition     {}", definition)?;
+    }
+    if let Some(simplified) = script_map.to_simplified(character.character) {
+        writeln!(out, "  simplified     {}", simplified)?;
+    }
+    let traditional: String = script_map.to_traditional(character.character).iter().collect();
+    if !traditional.is_empty() {
+        writeln!(out, "  traditional    {}", traditional)?;
+    }
+    writeln!(out, "  radical        {}", character.radical)?;
+    writeln!(out, "  strokes        {}", character.stroke_count())?;
+    writeln!(out, "  decomposition  {}", character.decomposition)?;
+    let Some(etymology) = &character.etymology else { return Ok(()) };
+    let kind = match etymology {
+        Etymology::Ideographic { .. } => "ideographic",
+        Etymology::Pictographic { .. } => "pictographic",
+        Etymology::Pictophonetic { .. } => "pictophonetic",
+    };
+    writeln!(out, "  etymology      {}", kind)?;
+    if let Etymology::Pictophonetic { semantic, phonetic, .. } = etymology {
+        if let Some(semantic) = semantic {
+            writeln!(out, "    semantic     {}", semantic)?;
+        }
+        if let Some(phonetic) = phonetic {
+            writeln!(out, "    phonetic     {}", phonetic)?;
+        }
+    }
+    if let Some(hint) = etymology.hint() {
+        writeln!(out, "    hint         {}", hint)?;
+    }
+    Ok(())
+}
+
+fn print_decomposition(out: &mut impl Write, decomposition: &Decomposition, dictionary: &[Character], depth: usize) -> io::Result<()> {
+    let indent = "  ".repeat(depth);
+    match decomposition {
+        Decomposition::Component(component) => {
+            let definition = dictionary.iter()
+                .find(|character| character.character == *component)
+                .and_then(|character| character.definition.as_deref())
+                .unwrap_or("");
+            writeln!(out, "{}{} {}", indent, component, definition)?;
+        }
+        Decomposition::Unknown => writeln!(out, "{}{}", indent, decomposition)?,
+        Decomposition::Compound { operator, components } => {
+            writeln!(out, "{}{} {}", indent, operator.to_char(), operator.description())?;
+            for component in components {
+                print_decomposition(out, component, dictionary, depth + 1)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn run(options: Options, out: &mut impl Write) -> Result<()> {
+    match options.command.as_str() {
+        "radical" => {
+            let argument = options.argument()?;
+            let radicals = load_radicals(&options.data_dir)?;
+            let radical = match argument.parse::<i32>() {
+                Ok(number) => radicals.iter().find(|radical| radical.number == number),
+                Err(_) => {
+                    let glyph = single_glyph(argument)?;
+                    radicals.iter().find(|radical| radical.radical_forms.contains(glyph))
+                }
+            }
+            .ok_or_else(|| anyhow!("no radical {}", argument))?;
+            if options.json {
+                writeln!(out, "{}", serde_json::to_string_pretty(radical)?)?;
+            } else {
+                print_radical(out, radical)?;
+            }
+        }
+        "char" => {
+            let glyph = single_glyph(options.argument()?)?;
+            let characters = load_characters(&options.data_dir)?;
+            let character = characters.iter()
+                .find(|character| character.character == glyph)
+                .ok_or_else(|| anyhow!("{} is not in the dictionary", glyph))?;
+            if options.json {
+                writeln!(out, "{}", serde_json::to_string_pretty(character)?)?;
+            } else {
+                let script_map = ScriptMap::build(&load_radicals(&options.data_dir)?, &characters);
+                print_character(out, character, &script_map)?;
+            }
+        }
+        "search" => {
+            let argument = options.argument()?;
+            let radicals = load_radicals(&options.data_dir)?;
+            let characters = load_characters(&options.data_dir)?;
+            let matching_radicals: Vec<&Radical> = radicals.iter()
+                .filter(|radical| radical.matches_search(argument))
+                .collect();
+            let matching_characters: Vec<&Character> = characters.iter()
+                .filter(|character| character.matches_search(argument))
+                .collect();
+            if options.json {
+                let results = serde_json::json!({
+                    "radicals": matching_radicals,
+                    "characters": matching_characters,
+                });
+                writeln!(out, "{}", serde_json::to_string_pretty(&results)?)?;
+            } else {
+                for radical in matching_radicals {
+                    let forms: String = radical.radical_forms.all().collect();
+                    writeln!(out, "radical {:>3}  {}  {}  {}", radical.number, forms, radical.pinyin.trim(), radical.meaning)?;
+                }
+                for character in matching_characters {
+                    writeln!(
+                        out,
+                        "char         {}  {}  {}",
+                        character.character,
+                        character.pinyin.join(", "),
+                        character.definition.as_deref().unwrap_or(""),
+                    )?;
+                }
+            }
+        }
+        "decompose" => {
+            let glyph = single_glyph(options.argument()?)?;
+            let characters = load_characters(&options.data_dir)?;
+            let character = characters.iter()
+                .find(|character| character.character == glyph)
+                .ok_or_else(|| anyhow!("{} is not in the dictionary", glyph))?;
+            let decomposition = character.parsed_decomposition()?;
+            if options.json {
+                writeln!(out, "{}", serde_json::to_string_pretty(&decomposition)?)?;
+            } else {
+                writeln!(out, "{} {}", character.character, character.decomposition)?;
+                print_decomposition(out, &decomposition, &characters, 1)?;
+            }
+        }
+        "check" => {
+            let path = options.data_dir.join(Radical::DATASET);
+            let text = fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+            let rows = Radical::parse_radical_rows(&text)?;
+            let characters = load_characters(&options.data_dir)?;
+            let report = DatasetReport::check(&rows, &characters);
+            if options.json {
+                writeln!(out, "{}", serde_json::to_string_pretty(&report)?)?;
+            } else {
+                for violation in &report.violations {
+                    writeln!(out, "{}", violation)?;
+                }
+            }
+            let errors = report.count(Severity::Error);
+            if errors > 0 {
+                bail!("{} errors and {} warnings found", errors, report.count(Severity::Warning));
+            }
+        }
+        "split-graphics" => {
+            let path = PathBuf::from(options.argument()?);
+            let text = fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+            let graphics = CharacterGraphics::parse_graphics(&text)?;
+            let directory = options.data_dir.join(CharacterGraphics::DIRECTORY);
+            fs::create_dir_all(&directory).with_context(|| format!("creating {}", directory.display()))?;
+            for entry in &graphics {
+                let path = options.data_dir.join(CharacterGraphics::file_name(entry.character));
+                fs::write(&path, serde_json::to_string(entry)?).with_context(|| format!("writing {}", path.display()))?;
+            }
+            writeln!(out, "wrote {} files to {}", graphics.len(), directory.display())?;
+        }
+        command => bail!("unknown command {:?}\n\n{}", command, USAGE),
+    }
+    Ok(())
+}
+
+fn main() {
+    let mut out = io::stdout().lock();
+    let data_dir = env::var_os("ZHONGCHAR_DATA_DIR").map(PathBuf::from);
+    let result = parse_options(env::args().skip(1), data_dir).and_then(|options| run(options, &mut out));
+    if let Err(error) = result {
+        // Piping into `head` and the like closes stdout early; that's not a failure.
+        let broken_pipe = error.downcast_ref::<io::Error>()
+            .is_some_and(|error| error.kind() == io::ErrorKind::BrokenPipe);
+        if broken_pipe {
+            return;
+        }
+        eprintln!("zhongchar: {:#}", error);
+        process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str], data_dir: Option<&str>) -> Result<Options> {
+        parse_options(args.iter().map(|arg| arg.to_string()), data_dir.map(PathBuf::from))
+    }
+
+    #[test]
+    fn parses_flags_anywhere_around_the_command() {
+        let options = parse(&["radical", "--json", "85"], None).unwrap();
+        assert!(options.json);
+        assert_eq!(options.command, "radical");
+        assert_eq!(options.argument().unwrap(), "85");
+        assert_eq!(options.data_dir, PathBuf::from("public"));
+
+        let options = parse(&["--data-dir", "data", "check"], Some("env")).unwrap();
+        assert!(!options.json);
+        assert_eq!(options.data_dir, PathBuf::from("data"));
+        assert_eq!(options.argument, None);
+        assert!(options.argument().is_err());
+
+        let options = parse(&["check"], Some("env")).unwrap();
+        assert_eq!(options.data_dir, PathBuf::from("env"));
+    }
+
+    #[test]
+    fn rejects_missing_and_extra_arguments() {
+        for args in [&[][..], &["--json"], &["char", "水", "火"], &["check", "--data-dir"]] {
+            assert!(parse(args, None).is_err(), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn splits_graphics_into_one_file_per_code_point() {
+        let data_dir = env::temp_dir().join(format!("zhongchar-split-graphics-{}", process::id()));
+        fs::create_dir_all(&data_dir).unwrap();
+        let source = data_dir.join(CharacterGraphics::DATASET);
+        fs::write(&source, concat!(
+            r#"{"character":"一","strokes":["M 100 500 L 900 500"],"medians":[[[100,500],[900,500]]]}"#, "\n",
+            r#"{"character":"𠂉","strokes":["M 500 900 L 500 100"],"medians":[[[500,900],[500,100]]]}"#, "\n",
+        )).unwrap();
+        let options = Options {
+            json: false,
+            data_dir: data_dir.clone(),
+            command: "split-graphics".to_string(),
+            argument: Some(source.to_string_lossy().into_owned()),
+        };
+        let mut out = Vec::new();
+        run(options, &mut out).unwrap();
+
+        let mut files = fs::read_dir(data_dir.join(CharacterGraphics::DIRECTORY)).unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        files.sort();
+        assert_eq!(files, ["20089.json", "4e00.json"]);
+        let one = fs::read_to_string(data_dir.join(CharacterGraphics::file_name('一'))).unwrap();
+        assert_eq!(CharacterGraphics::parse_character_graphics(&one).unwrap().medians, [vec![(100, 500), (900, 500)]]);
+        assert!(String::from_utf8(out).unwrap().starts_with("wrote 2 files to "));
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+}
@@ -0,0 +1,372 @@
+//! A JSON backup of everything the learner has recorded, and the rules for
+//! restoring one on top of existing data.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::BackupError;
+use crate::flashcards::{CardId, ReviewRecord};
+use crate::radical::Radical;
+use crate::scheduler::{CardState, Leitner, Memory, Timestamp};
+use crate::tracing::TracingStats;
+
+/// Identifies a backup file, so restoring some other JSON fails clearly.
+pub const BACKUP_FORMAT: &str = "zhongchar-backup";
+/// Bump when the backup layout changes; older backups must stay readable.
+pub const BACKUP_VERSION: u32 = 1;
+
+/// A flashcard's scheduling state, stored with its id.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CardRecord {
+    pub card: CardId,
+    pub state: CardState,
+}
+
+/// Tracing results for one character, stored with the character.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TracingRecord {
+    pub character: char,
+    pub stats: TracingStats,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Backup {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: Timestamp,
+    pub cards: Vec<CardRecord>,
+    /// Oldest first.
+    pub reviews: Vec<ReviewRecord>,
+    pub tracing: Vec<TracingRecord>,
+    /// Saved preferences, such as the chosen scheduler.
+    pub settings: BTreeMap<String, String>,
+}
+
+/// How a restored backup combines with the data already stored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RestoreMode {
+    /// Keep existing data and add the backup's: for each card the more
+    /// recently reviewed state wins, reviews are combined, each character
+    /// keeps whichever tracing stats have more attempts, and existing
+    /// settings are kept.
+    #[default]
+    Merge,
+    /// Discard existing data and use the backup's alone.
+    Replace,
+}
+
+impl Backup {
+    pub fn new(
+        exported_at: Timestamp,
+        cards: Vec<CardRecord>,
+        reviews: Vec<ReviewRecord>,
+        tracing: Vec<TracingRecord>,
+        settings: BTreeMap<String, String>,
+    ) -> Backup {
+        Backup {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION,
+            exported_at,
+            cards,
+            reviews,
+            tracing,
+            settings,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("backups contain only JSON-representable values")
+    }
+
+    /// Reads a backup, rejecting files that aren't backups, come from a newer
+    /// version, or hold values the app couldn't have written.
+    pub fn parse(text: &str) -> Result<Backup, BackupError> {
+        let value: Value = serde_json::from_str(text)?;
+        if value.get("format").and_then(Value::as_str) != Some(BACKUP_FORMAT) {
+            return Err(BackupError::NotABackup);
+        }
+        let version = value.get("version").and_then(Value::as_u64).ok_or(BackupError::NotABackup)?;
+        if version > BACKUP_VERSION as u64 {
+            return Err(BackupError::UnsupportedVersion { found: version as u32, supported: BACKUP_VERSION });
+        }
+        let backup: Backup = serde_json::from_value(value)?;
+        backup.validate()?;
+        Ok(backup)
+    }
+
+    fn validate(&self) -> Result<(), BackupError> {
+        let mut cards = BTreeSet::new();
+        for (index, record) in self.cards.iter().enumerate() {
+            let location = format!("cards[{}]", index);
+            check_radical(&location, record.card.radical)?;
+            if !cards.insert(record.card) {
+                return Err(invalid(&location, format!("card {} appears more than once", record.card.key())));
+            }
+            check_timestamp(&format!("{}.state.due", location), record.state.due)?;
+            check_timestamp(&format!("{}.state.last_reviewed", location), record.state.last_reviewed)?;
+            let memory = match record.state.memory {
+                Memory::Sm2 { ease, interval_days, .. } => vec![("ease", ease), ("interval_days", interval_days)],
+                Memory::Fsrs { stability, difficulty } => vec![("stability", stability), ("difficulty", difficulty)],
+                Memory::Leitner { level } => {
+                    if !(1..=Leitner::LEVELS).contains(&level) {
+                        return Err(invalid(
+                            &format!("{}.state.memory.level", location),
+                            format!("{} is not a box between 1 and {}", level, Leitner::LEVELS),
+                        ));
+                    }
+                    Vec::new()
+                }
+            };
+            for (name, value) in memory {
+                if !value.is_finite() || value < 0.0 {
+                    return Err(invalid(&format!("{}.state.memory.{}", location, name), format!("{} is not a valid value", value)));
+                }
+            }
+        }
+        for (index, review) in self.reviews.iter().enumerate() {
+            let location = format!("reviews[{}]", index);
+            check_radical(&location, review.card.radical)?;
+            check_timestamp(&format!("{}.reviewed_at", location), review.reviewed_at)?;
+        }
+        let mut characters = BTreeSet::new();
+        for (index, record) in self.tracing.iter().enumerate() {
+            let location = format!("tracing[{}]", index);
+            if !characters.insert(record.character) {
+                return Err(invalid(&location, format!("{} appears more than once", record.character)));
+            }
+            let stats = record.stats;
+            for (name, value) in [("best_accuracy", stats.best_accuracy), ("last_accuracy", stats.last_accuracy)] {
+                if !(0.0..=1.0).contains(&value) {
+                    return Err(invalid(&format!("{}.stats.{}", location, name), format!("{} is not between 0 and 1", value)));
+                }
+            }
+            // Allow for rounding in the running sum.
+            if !(0.0..=stats.attempts as f64 + 1e-9).contains(&stats.total_accuracy) {
+                return Err(invalid(
+                    &format!("{}.stats.total_accuracy", location),
+                    format!("{} is not between 0 and the number of attempts", stats.total_accuracy),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The data to store after restoring `incoming` over `self`.
+    pub fn restore(self, incoming: Backup, mode: RestoreMode) -> Backup {
+        if mode == RestoreMode::Replace {
+            return incoming;
+        }
+
+        let mut cards = self.cards.into_iter()
+            .map(|record| (record.card, record))
+            .collect::<BTreeMap<_, _>>();
+        for record in incoming.cards {
+            cards.entry(record.card)
+                .and_modify(|existing| {
+                    if record.state.last_reviewed > existing.state.last_reviewed {
+                        *existing = record;
+                    }
+                })
+                .or_insert(record);
+        }
+
+        let mut reviews = self.reviews;
+        let seen = reviews.iter()
+            .map(|review| (review.reviewed_at.to_bits(), review.card))
+            .collect::<BTreeSet<_>>();
+        reviews.extend(incoming.reviews.into_iter().filter(|review| !seen.contains(&(review.reviewed_at.to_bits(), review.card))));
+        reviews.sort_by(|a, b| a.reviewed_at.total_cmp(&b.reviewed_at));
+
+        let mut tracing = self.tracing.into_iter()
+            .map(|record| (record.character, record))
+            .collect::<BTreeMap<_, _>>();
+        for record in incoming.tracing {
+            tracing.entry(record.character)
+                .and_modify(|existing| {
+                    if record.stats.attempts > existing.stats.attempts {
+                        *existing = record;
+                    }
+                })
+                .or_insert(record);
+        }
+
+        let mut settings = incoming.settings;
+        settings.extend(self.settings);
+
+        Backup::new(
+            self.exported_at.max(incoming.exported_at),
+            cards.into_values().collect(),
+            reviews,
+            tracing.into_values().collect(),
+            settings,
+        )
+    }
+}
+
+fn invalid(location: &str, problem: String) -> BackupError {
+    BackupError::Invalid { location: location.to_string(), problem }
+}
+
+fn check_radical(location: &str, radical: i32) -> Result<(), BackupError> {
+    if !(1..=Radical::COUNT).contains(&radical) {
+        return Err(invalid(&format!("{}.card.radical", location), format!("there is no radical {}", radical)));
+    }
+    Ok(())
+}
+
+fn check_timestamp(location: &str, timestamp: Timestamp) -> Result<(), BackupError> {
+    if !timestamp.is_finite() || timestamp < 0.0 {
+        return Err(invalid(location, format!("{} is not a valid time", timestamp)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flashcards::CardKind;
+    use crate::scheduler::{Grade, SchedulerKind};
+
+    const DAY: Timestamp = 86_400_000.0;
+
+    fn card(radical: i32, last_reviewed: Timestamp) -> CardRecord {
+        CardRecord {
+            card: CardId { radical, kind: CardKind::GlyphToMeaning },
+            state: CardState {
+                due: last_reviewed + DAY,
+                last_reviewed,
+                reviews: 1,
+                lapses: 0,
+                recalled: true,
+                memory: Memory::Leitner { level: 1 },
+            },
+        }
+    }
+
+    fn review(radical: i32, reviewed_at: Timestamp) -> ReviewRecord {
+        ReviewRecord {
+            card: CardId { radical, kind: CardKind::GlyphToMeaning },
+            grade: Grade::Good,
+            reviewed_at,
+            scheduler: SchedulerKind::Leitner,
+        }
+    }
+
+    fn tracing(character: char, attempts: u32) -> TracingRecord {
+        let accuracy = 0.5;
+        TracingRecord {
+            character,
+            stats: TracingStats {
+                attempts,
+                total_accuracy: accuracy * attempts as f64,
+                best_accuracy: accuracy,
+                last_accuracy: accuracy,
+            },
+        }
+    }
+
+    fn sample() -> Backup {
+        Backup::new(
+            10.0 * DAY,
+            vec![card(1, DAY), card(9, 2.0 * DAY)],
+            vec![review(1, DAY), review(9, 2.0 * DAY)],
+            vec![tracing('人', 3)],
+            BTreeMap::from([("scheduler".to_string(), "leitner".to_string())]),
+        )
+    }
+
+    fn invalid_location(backup: &Backup) -> String {
+        match Backup::parse(&backup.to_json()) {
+            Err(BackupError::Invalid { location, .. }) => location,
+            other => panic!("expected an invalid backup, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_its_own_output() {
+        let backup = sample();
+        assert_eq!(Backup::parse(&backup.to_json()).unwrap(), backup);
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_backups() {
+        assert!(matches!(Backup::parse("not json"), Err(BackupError::Json(_))));
+        assert!(matches!(Backup::parse(r#"{"format": "something-else", "version": 1}"#), Err(BackupError::NotABackup)));
+        assert!(matches!(Backup::parse(r#"{"format": "zhongchar-backup"}"#), Err(BackupError::NotABackup)));
+    }
+
+    #[test]
+    fn rejects_backups_from_newer_versions() {
+        let mut backup = sample();
+        backup.version = BACKUP_VERSION + 1;
+        assert!(matches!(
+            Backup::parse(&backup.to_json()),
+            Err(BackupError::UnsupportedVersion { found, supported }) if found == BACKUP_VERSION + 1 && supported == BACKUP_VERSION
+        ));
+    }
+
+    #[test]
+    fn rejects_values_the_app_could_not_have_written() {
+        let mut backup = sample();
+        backup.cards[1].card.radical = 0;
+        assert_eq!(invalid_location(&backup), "cards[1].card.radical");
+
+        let mut backup = sample();
+        backup.cards.push(card(1, 3.0 * DAY));
+        assert_eq!(invalid_location(&backup), "cards[2]");
+
+        let mut backup = sample();
+        backup.cards[0].state.due = -1.0;
+        assert_eq!(invalid_location(&backup), "cards[0].state.due");
+
+        for level in [0, Leitner::LEVELS + 1] {
+            let mut backup = sample();
+            backup.cards[0].state.memory = Memory::Leitner { level };
+            assert_eq!(invalid_location(&backup), "cards[0].state.memory.level");
+        }
+
+        let mut backup = sample();
+        backup.cards[0].state.memory = Memory::Fsrs { stability: -1.0, difficulty: 5.0 };
+        assert_eq!(invalid_location(&backup), "cards[0].state.memory.stability");
+
+        let mut backup = sample();
+        backup.tracing[0].stats.best_accuracy = 1.5;
+        assert_eq!(invalid_location(&backup), "tracing[0].stats.best_accuracy");
+
+        for total_accuracy in [-0.5, 3.5] {
+            let mut backup = sample();
+            backup.tracing[0].stats.total_accuracy = total_accuracy;
+            assert_eq!(invalid_location(&backup), "tracing[0].stats.total_accuracy");
+        }
+    }
+
+    #[test]
+    fn replace_discards_existing_data() {
+        let incoming = Backup::new(0.0, Vec::new(), Vec::new(), Vec::new(), BTreeMap::new());
+        assert_eq!(sample().restore(incoming.clone(), RestoreMode::Replace), incoming);
+    }
+
+    #[test]
+    fn merge_keeps_the_most_recent_progress() {
+        let incoming = Backup::new(
+            20.0 * DAY,
+            vec![card(1, 5.0 * DAY), card(9, DAY), card(30, DAY)],
+            vec![review(1, 5.0 * DAY), review(1, DAY)],
+            vec![tracing('人', 1), tracing('口', 2)],
+            BTreeMap::from([
+                ("scheduler".to_string(), "sm2".to_string()),
+                ("new_cards".to_string(), "5".to_string()),
+            ]),
+        );
+        let merged = sample().restore(incoming, RestoreMode::Merge);
+
+        assert_eq!(merged.exported_at, 20.0 * DAY);
+        assert_eq!(merged.cards, vec![card(1, 5.0 * DAY), card(9, 2.0 * DAY), card(30, DAY)]);
+        assert_eq!(merged.reviews, vec![review(1, DAY), review(9, 2.0 * DAY), review(1, 5.0 * DAY)]);
+        assert_eq!(merged.tracing, vec![tracing('人', 3), tracing('口', 2)]);
+        assert_eq!(merged.settings["scheduler"], "leitner");
+        assert_eq!(merged.settings["new_cards"], "5");
+    }
+}
@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+
+use super::error::{IdsError, ZhongCharError, ZhongCharResult};
+use super::ids::Decomposition;
+
+/// Path from the root of a character's decomposition to one of its components,
+/// e.g. `[1, 0]` is the first child of the second top-level component.
+pub type ComponentPath = Vec<usize>;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Character {
+    pub character: char,
+    pub definition: Option<String>,
+    pub pinyin: Vec<String>,
+    pub decomposition: String,
+    pub etymology: Option<Etymology>,
+    pub radical: char,
+    /// One entry per stroke: the component the stroke belongs to, or `None`
+    /// when the stroke isn't part of any component in the decomposition.
+    pub matches: Vec<Option<ComponentPath>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Etymology {
+    Ideographic {
+        hint: Option<String>,
+    },
+    Pictographic {
+        hint: Option<String>,
+    },
+    Pictophonetic {
+        phonetic: Option<String>,
+        semantic: Option<String>,
+        hint: Option<String>,
+    },
+}
+
+impl Etymology {
+    pub fn hint(&self) -> Option<&str> {
+        match self {
+            Etymology::Ideographic { hint }
+            | Etymology::Pictographic { hint }
+            | Etymology::Pictophonetic { hint, .. } => hint.as_deref(),
+        }
+    }
+
+    /// Whether a pictophonetic character names `component` as its semantic
+    /// or phonetic part.
+    pub fn role_of(&self, component: char) -> Option<ComponentRole> {
+        let Etymology::Pictophonetic { semantic, phonetic, .. } = self else {
+            return None;
+        };
+        if semantic.as_deref().is_some_and(|semantic| semantic.contains(component)) {
+            Some(ComponentRole::Semantic)
+        } else if phonetic.as_deref().is_some_and(|phonetic| phonetic.contains(component)) {
+            Some(ComponentRole::Phonetic)
+        } else {
+            None
+        }
+    }
+}
+
+/// The part a component plays in a pictophonetic character.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComponentRole {
+    /// Hints at the meaning.
+    Semantic,
+    /// Hints at the pronunciation.
+    Phonetic,
+}
+
+impl Character {
+    pub const DATASET: &'static str = "dictionary.txt";
+
+    /// Parses Make Me a Hanzi's `dictionary.txt`, one JSON object per line.
+    pub fn parse_dictionary(text: &str) -> ZhongCharResult<Vec<Character>> {
+        let mut characters: Vec<Character> = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() { continue };
+            let character = serde_json::from_str(line)
+                .map_err(|source| ZhongCharError::DictionaryLine { line: index + 1, source })?;
+            characters.push(character);
+        }
+
+        Ok(characters)
+    }
+
+    /// `matches` has one entry per stroke, so it doubles as the stroke count.
+    pub fn stroke_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    pub fn parsed_decomposition(&self) -> Result<Decomposition, IdsError> {
+        Decomposition::parse(&self.decomposition)
+    }
+
+    /// Strokes belonging to the component at `path` or any part of it.
+    pub fn strokes_of(&self, path: &[usize]) -> Vec<usize> {
+        self.matches.iter()
+            .enumerate()
+            .filter(|(_, component)| component.as_ref().is_some_and(|component| component.starts_with(path)))
+            .map(|(stroke, _)| stroke)
+            .collect()
+    }
+
+    /// The etymological role of each stroke's component. A stroke inside a
+    /// sub-component takes the role of the outermost component that has one,
+    /// so every stroke of a phonetic `胡` counts as phonetic.
+    pub fn stroke_roles(&self, decomposition: &Decomposition) -> Vec<Option<ComponentRole>> {
+        self.matches.iter()
+            .map(|component| {
+                let path = component.as_deref()?;
+                (1..=path.len()).find_map(|depth| self.component_role(decomposition, &path[..depth]))
+            })
+            .collect()
+    }
+
+    /// The etymological role of the component at `path`, if it's a single
+    /// named component the etymology mentions.
+    pub fn component_role(&self, decomposition: &Decomposition, path: &[usize]) -> Option<ComponentRole> {
+        let Decomposition::Component(component) = decomposition.get(path)? else {
+            return None;
+        };
+        self.etymology.as_ref()?.role_of(*component)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GOOD: &str = r#"{"character":"好","definition":"good, excellent, fine; proper, suitable; well","pinyin":["hǎo"],"decomposition":"⿰女子","etymology":{"type":"ideographic","hint":"A woman 女 with a son 子"},"radical":"女","matches":[[0],[0],[0],[1],[1],[1]]}"#;
+    const ICE: &str = r#"{"character":"⺀","definition":"ice","pinyin":[],"decomposition":"？","radical":"⺀","matches":[null,null]}"#;
+
+    #[test]
+    fn parses_one_character_per_line() {
+        let characters = Character::parse_dictionary(&format!("{}\n\n{}\n", GOOD, ICE)).unwrap();
+        assert_eq!(characters.len(), 2);
+        let good = &characters[0];
+        assert_eq!(good.character, '好');
+        assert_eq!(good.pinyin, ["hǎo"]);
+        assert_eq!(good.radical, '女');
+        assert_eq!(good.stroke_count(), 6);
+        assert_eq!(good.etymology.as_ref().and_then(Etymology::hint), Some("A woman 女 with a son 子"));
+        assert_eq!(good.parsed_decomposition().unwrap().leaves(), ['女', '子']);
+        let ice = &characters[1];
+        assert_eq!(ice.etymology, None);
+        assert!(ice.parsed_decomposition().unwrap().is_unknown());
+        assert_eq!(ice.matches, [None, None]);
+    }
+
+    #[test]
+    fn reports_the_line_of_malformed_json() {
+        let text = format!("{}\n{}\n{{\"character\":\"好\",", GOOD, ICE);
+        match Character::parse_dictionary(&text) {
+            Err(ZhongCharError::DictionaryLine { line, .. }) => assert_eq!(line, 3),
+            other => panic!("expected a dictionary line error, got {:?}", other),
+        }
+        // A line that is valid JSON but not a character is rejected too.
+        match Character::parse_dictionary(r#"{"character":"好"}"#) {
+            Err(ZhongCharError::DictionaryLine { line, .. }) => assert_eq!(line, 1),
+            other => panic!("expected a dictionary line error, got {:?}", other),
+        }
+    }
+
+    /// 湖 with 胡 split further, so strokes can sit two levels deep.
+    fn lake() -> Character {
+        let line = r#"{"character":"湖","pinyin":["hú"],"decomposition":"⿰氵⿰古⺼","etymology":{"type":"pictophonetic","phonetic":"古","semantic":"氵"},"radical":"氵","matches":[[0],[0],[0],[1,0],[1,0],[1,1],null]}"#;
+        Character::parse_dictionary(line).unwrap().remove(0)
+    }
+
+    #[test]
+    fn finds_the_strokes_of_a_component_and_its_parts() {
+        let lake = lake();
+        assert_eq!(lake.strokes_of(&[0]), [0, 1, 2]);
+        assert_eq!(lake.strokes_of(&[1]), [3, 4, 5]);
+        assert_eq!(lake.strokes_of(&[1, 0]), [3, 4]);
+        assert_eq!(lake.strokes_of(&[2]), Vec::<usize>::new());
+        assert_eq!(lake.strokes_of(&[]), [0, 1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn gives_each_stroke_the_role_of_its_component() {
+        let lake = lake();
+        let decomposition = lake.parsed_decomposition().unwrap();
+        use ComponentRole::{Phonetic, Semantic};
+        assert_eq!(
+            lake.stroke_roles(&decomposition),
+            [Some(Semantic), Some(Semantic), Some(Semantic), Some(Phonetic), Some(Phonetic), None, None],
+        );
+    }
+
+    #[test]
+    fn outer_components_decide_the_role() {
+        let line = r#"{"character":"湖","pinyin":["hú"],"decomposition":"⿰氵胡","etymology":{"type":"pictophonetic","phonetic":"胡","semantic":"氵"},"radical":"氵","matches":[[0],[1],[1,0],[1,1]]}"#;
+        let lake = Character::parse_dictionary(line).unwrap().remove(0);
+        let decomposition = lake.parsed_decomposition().unwrap();
+        let roles = lake.stroke_roles(&decomposition);
+        assert_eq!(roles, [Some(ComponentRole::Semantic), Some(ComponentRole::Phonetic), Some(ComponentRole::Phonetic), Some(ComponentRole::Phonetic)]);
+        assert_eq!(lake.component_role(&decomposition, &[1, 0]), None);
+    }
+}
@@ -0,0 +1,128 @@
+use std::{sync::Arc};
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone)]
+pub enum ArcZhongCharError {
+    #[error("{0}")]
+    Error(Arc<ZhongCharError>),
+
+}
+
+impl From<ZhongCharError> for ArcZhongCharError {
+    fn from(error: ZhongCharError) -> Self {
+        ArcZhongCharError::Error(Arc::new(error))
+    }
+}
+
+pub type ZhongCharResult<T> = Result<T, ZhongCharError>;
+
+#[derive(Error, Debug)]
+#[error(transparent)]
+pub enum ZhongCharError {
+    #[error("{0}")]
+    Wasm(String),
+    #[error("{0}")]
+    Network(String),
+    #[error("{url} responded with HTTP {status}")]
+    Http {
+        url: String,
+        status: u16,
+    },
+    #[error("{url} did not respond within {seconds} seconds")]
+    Timeout {
+        url: String,
+        seconds: u64,
+    },
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+    #[error("line {line}, column {column:?}: {message}")]
+    CsvField {
+        line: u64,
+        column: String,
+        message: String,
+    },
+    #[error("dictionary line {line}: {source}")]
+    DictionaryLine {
+        line: usize,
+        source: serde_json::Error,
+    },
+    #[error("graphics line {line}: {source}")]
+    GraphicsLine {
+        line: usize,
+        source: serde_json::Error,
+    },
+    #[error(transparent)]
+    Ids(#[from] IdsError),
+    #[error(transparent)]
+    Pinyin(#[from] PinyinError),
+    #[error("{0}")]
+    IndexedDb(String),
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum IdsError {
+    #[error("empty ideographic description sequence")]
+    Empty,
+    #[error("operator {operator} at position {position} expects {expected} components but found {found}")]
+    MissingOperand {
+        operator: char,
+        position: usize,
+        expected: usize,
+        found: usize,
+    },
+    #[error("unexpected {found} at position {position} after a complete sequence")]
+    TrailingComponent {
+        position: usize,
+        found: char,
+    },
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum PinyinError {
+    #[error("empty pinyin syllable")]
+    Empty,
+    #[error("{0} is not a tone number")]
+    InvalidToneNumber(u32),
+    #[error("{syllable} contains {found}, which is not a pinyin letter")]
+    InvalidCharacter {
+        syllable: String,
+        found: char,
+    },
+    #[error("{0} is marked with more than one tone")]
+    ConflictingTones(String),
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    #[error("unknown field {{{name}}}; available fields are {available}")]
+    UnknownField {
+        name: String,
+        available: String,
+    },
+    #[error("{{ at position {position} is never closed")]
+    Unclosed {
+        position: usize,
+    },
+    #[error("}} at position {position} has no matching {{")]
+    Unmatched {
+        position: usize,
+    },
+}
+
+#[derive(Error, Debug)]
+pub enum BackupError {
+    #[error("could not read the backup: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("this file is not a 中 Char backup")]
+    NotABackup,
+    #[error("backup version {found} is newer than this version of the app supports ({supported})")]
+    UnsupportedVersion {
+        found: u32,
+        supported: u32,
+    },
+    #[error("{location}: {problem}")]
+    Invalid {
+        location: String,
+        problem: String,
+    },
+}
@@ -0,0 +1,250 @@
+//! Tab-separated exports that Anki can import, with each column filled from a
+//! template such as `{glyph} ({pinyin})`.
+
+use std::collections::BTreeMap;
+
+use crate::error::TemplateError;
+use crate::flashcards::{Card, CardKind};
+use crate::radical::Radical;
+
+/// Placeholders available for every row, filled from the row's radical.
+pub const RADICAL_FIELDS: [&str; 13] = [
+    "number", "glyph", "forms", "strokes", "meaning", "colloquial", "pinyin", "han_viet", "japanese",
+    "korean", "frequency", "examples", "simplified",
+];
+/// Extra placeholders available when exporting flashcards.
+pub const CARD_FIELDS: [&str; 4] = ["prompt", "answer", "detail", "kind"];
+
+/// A column template: literal text with `{field}` placeholders. `{{` and `}}`
+/// stand for literal braces. Literal text is copied as written, so it may
+/// contain HTML such as `<br>`; field values are escaped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldTemplate {
+    parts: Vec<TemplatePart>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TemplatePart {
+    Text(String),
+    Field(String),
+}
+
+impl FieldTemplate {
+    /// Parses `text`, accepting only placeholders named in `fields`.
+    pub fn parse(text: &str, fields: &[&str]) -> Result<FieldTemplate, TemplateError> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = text.char_indices().peekable();
+        while let Some((position, c)) = chars.next() {
+            match c {
+                '{' if chars.next_if(|(_, next)| *next == '{').is_some() => literal.push('{'),
+                '}' if chars.next_if(|(_, next)| *next == '}').is_some() => literal.push('}'),
+                '}' => return Err(TemplateError::Unmatched { position }),
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some((_, '}')) => break,
+                            Some((_, c)) => name.push(c),
+                            None => return Err(TemplateError::Unclosed { position }),
+                        }
+                    }
+                    let name = name.trim().to_string();
+                    if !fields.contains(&name.as_str()) {
+                        return Err(TemplateError::UnknownField { name, available: fields.join(", ") });
+                    }
+                    if !literal.is_empty() {
+                        parts.push(TemplatePart::Text(std::mem::take(&mut literal)));
+                    }
+                    parts.push(TemplatePart::Field(name));
+                }
+                _ => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            parts.push(TemplatePart::Text(literal));
+        }
+        Ok(FieldTemplate { parts })
+    }
+
+    fn render(&self, values: &BTreeMap<&'static str, String>, escape: fn(&str) -> String) -> String {
+        self.parts.iter()
+            .map(|part| match part {
+                TemplatePart::Text(text) => text.clone(),
+                TemplatePart::Field(name) => values.get(name.as_str()).map(|value| escape(value)).unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
+/// Which rows to export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportSource {
+    /// One row per radical.
+    RadicalTable,
+    /// One row per flashcard of the given kind.
+    Deck(CardKind),
+}
+
+impl ExportSource {
+    /// Placeholders the templates may use for this source.
+    pub fn fields(self) -> Vec<&'static str> {
+        match self {
+            ExportSource::RadicalTable => RADICAL_FIELDS.to_vec(),
+            ExportSource::Deck(_) => CARD_FIELDS.iter().chain(RADICAL_FIELDS.iter()).copied().collect(),
+        }
+    }
+}
+
+/// Front, back and tags column templates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnkiTemplates {
+    pub front: String,
+    pub back: String,
+    /// Rendered into Anki's space-separated tag list; spaces inside a field
+    /// value become underscores so each value stays one tag.
+    pub tags: String,
+}
+
+impl AnkiTemplates {
+    pub fn defaults(source: ExportSource) -> AnkiTemplates {
+        match source {
+            ExportSource::RadicalTable => AnkiTemplates {
+                front: "{glyph}".to_string(),
+                back: "{meaning}<br>{pinyin}".to_string(),
+                tags: "zhongchar radical strokes_{strokes}".to_string(),
+            },
+            ExportSource::Deck(_) => AnkiTemplates {
+                front: "{prompt}".to_string(),
+                back: "{answer}<br>{detail}".to_string(),
+                tags: "zhongchar {kind}".to_string(),
+            },
+        }
+    }
+}
+
+/// Renders an Anki import file: a header saying the file is tab-separated
+/// HTML with tags in the third column, then one line per row.
+pub fn anki_tsv(radicals: &[Radical], source: ExportSource, templates: &AnkiTemplates) -> Result<String, TemplateError> {
+    let fields = source.fields();
+    let front = FieldTemplate::parse(&templates.front, &fields)?;
+    let back = FieldTemplate::parse(&templates.back, &fields)?;
+    let tags = FieldTemplate::parse(&templates.tags, &fields)?;
+
+    let mut tsv = String::from("#separator:tab\n#html:true\n#tags column:3\n");
+    for radical in radicals {
+        let mut values = radical_values(radical);
+        if let ExportSource::Deck(kind) = source {
+            let card = Card::for_radical(radical, kind);
+            values.insert("prompt", card.prompt);
+            values.insert("answer", card.answer);
+            values.insert("detail", card.detail.unwrap_or_default());
+            values.insert("kind", kind.key().to_string());
+        }
+        let columns = [
+            front.render(&values, escape_html),
+            back.render(&values, escape_html),
+            tags.render(&values, tag_value),
+        ];
+        let line = columns.iter().map(|column| tsv_cell(column)).collect::<Vec<_>>().join("\t");
+        tsv.push_str(&line);
+        tsv.push('\n');
+    }
+    Ok(tsv)
+}
+
+fn radical_values(radical: &Radical) -> BTreeMap<&'static str, String> {
+    BTreeMap::from([
+        ("number", radical.number.to_string()),
+        ("glyph", radical.radical_forms.primary.to_string()),
+        ("forms", radical.radical_forms.all().map(String::from).collect::<Vec<_>>().join(" ")),
+        ("strokes", radical.stroke_count.to_string()),
+        ("meaning", radical.meaning.clone()),
+        ("colloquial", radical.colloquial_term.clone().unwrap_or_default()),
+        ("pinyin", radical.pinyin.clone()),
+        ("han_viet", radical.han_viet.clone()),
+        ("japanese", radical.hiragana_romaji.to_string()),
+        ("korean", radical.hangul_romaja.to_string()),
+        ("frequency", radical.frequency.to_string()),
+        ("examples", radical.examples.iter().collect()),
+        ("simplified", radical.simplified.as_ref().map(|simplified| simplified.to_string()).unwrap_or_default()),
+    ])
+}
+
+fn escape_html(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn tag_value(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join("_")
+}
+
+/// Tabs and line breaks would start a new column or row.
+fn tsv_cell(text: &str) -> String {
+    text.replace('\t', " ").replace("\r\n", "<br>").replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIELDS: &[&str] = &["glyph", "pinyin", "meaning"];
+
+    fn parts(text: &str) -> Vec<TemplatePart> {
+        FieldTemplate::parse(text, FIELDS).unwrap().parts
+    }
+
+    #[test]
+    fn splits_templates_into_text_and_fields() {
+        assert_eq!(parts("{glyph} ({pinyin})"), vec![
+            TemplatePart::Field("glyph".to_string()),
+            TemplatePart::Text(" (".to_string()),
+            TemplatePart::Field("pinyin".to_string()),
+            TemplatePart::Text(")".to_string()),
+        ]);
+        assert_eq!(parts("{ meaning }"), vec![TemplatePart::Field("meaning".to_string())]);
+        assert_eq!(parts(""), Vec::new());
+    }
+
+    #[test]
+    fn doubled_braces_are_literal() {
+        assert_eq!(parts("{{glyph}}"), vec![TemplatePart::Text("{glyph}".to_string())]);
+        assert_eq!(parts("{{{glyph}}}"), vec![
+            TemplatePart::Text("{".to_string()),
+            TemplatePart::Field("glyph".to_string()),
+            TemplatePart::Text("}".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn reports_malformed_templates() {
+        assert_eq!(FieldTemplate::parse("a}", FIELDS), Err(TemplateError::Unmatched { position: 1 }));
+        assert_eq!(FieldTemplate::parse("ab{glyph", FIELDS), Err(TemplateError::Unclosed { position: 2 }));
+        assert_eq!(
+            FieldTemplate::parse("{strokes}", FIELDS),
+            Err(TemplateError::UnknownField { name: "strokes".to_string(), available: "glyph, pinyin, meaning".to_string() }),
+        );
+    }
+
+    #[test]
+    fn escapes_field_values_but_not_literal_text() {
+        let template = FieldTemplate::parse("<b>{meaning}</b>", FIELDS).unwrap();
+        let values = BTreeMap::from([("meaning", "<fish & chips>".to_string())]);
+        assert_eq!(template.render(&values, escape_html), "<b>&lt;fish &amp; chips&gt;</b>");
+        let tags = FieldTemplate::parse("zhongchar {meaning}", FIELDS).unwrap();
+        assert_eq!(tags.render(&values, tag_value), "zhongchar <fish_&_chips>");
+    }
+
+    #[test]
+    fn keeps_cells_on_one_line() {
+        assert_eq!(tsv_cell("a\tb\nc\r\nd"), "a b<br>c<br>d");
+    }
+
+    #[test]
+    fn anki_tsv_checks_every_template() {
+        let templates = AnkiTemplates { tags: "{prompt}".to_string(), ..AnkiTemplates::defaults(ExportSource::RadicalTable) };
+        assert!(matches!(anki_tsv(&[], ExportSource::RadicalTable, &templates), Err(TemplateError::UnknownField { .. })));
+        let deck = ExportSource::Deck(crate::flashcards::CardKind::GlyphToMeaning);
+        assert_eq!(anki_tsv(&[], deck, &templates).unwrap(), "#separator:tab\n#html:true\n#tags column:3\n");
+    }
+}
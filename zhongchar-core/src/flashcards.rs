@@ -0,0 +1,266 @@
+//! Flashcards generated from the radical table.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::radical::Radical;
+use crate::scheduler::{CardState, Grade, SchedulerKind, Timestamp};
+
+/// Which side of a radical a card asks about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CardKind {
+    GlyphToMeaning,
+    MeaningToGlyph,
+    GlyphToPinyin,
+}
+
+impl CardKind {
+    pub const ALL: [CardKind; 3] = [CardKind::GlyphToMeaning, CardKind::MeaningToGlyph, CardKind::GlyphToPinyin];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            CardKind::GlyphToMeaning => "glyph_to_meaning",
+            CardKind::MeaningToGlyph => "meaning_to_glyph",
+            CardKind::GlyphToPinyin => "glyph_to_pinyin",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<CardKind> {
+        CardKind::ALL.into_iter().find(|kind| kind.key() == key)
+    }
+
+    pub fn prompt_is_glyph(self) -> bool {
+        matches!(self, CardKind::GlyphToMeaning | CardKind::GlyphToPinyin)
+    }
+
+    pub fn answer_is_glyph(self) -> bool {
+        self == CardKind::MeaningToGlyph
+    }
+}
+
+impl fmt::Display for CardKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CardKind::GlyphToMeaning => write!(f, "Glyph → meaning"),
+            CardKind::MeaningToGlyph => write!(f, "Meaning → glyph"),
+            CardKind::GlyphToPinyin => write!(f, "Glyph → pinyin"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CardId {
+    /// The radical's Kangxi number.
+    pub radical: i32,
+    pub kind: CardKind,
+}
+
+impl CardId {
+    /// A stable string form, e.g. `9/glyph_to_meaning`, for use as a storage key.
+    pub fn key(&self) -> String {
+        format!("{}/{}", self.radical, self.kind.key())
+    }
+
+    pub fn from_key(key: &str) -> Option<CardId> {
+        let (radical, kind) = key.split_once('/')?;
+        Some(CardId { radical: radical.parse().ok()?, kind: CardKind::from_key(kind)? })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Card {
+    pub id: CardId,
+    pub prompt: String,
+    pub answer: String,
+    /// Shown with the answer as a reminder, e.g. the pinyin on a glyph card.
+    pub detail: Option<String>,
+}
+
+impl Card {
+    pub fn for_radical(radical: &Radical, kind: CardKind) -> Card {
+        let glyph = radical.radical_forms.primary.to_string();
+        let all_forms = radical.radical_forms.all()
+            .map(|form| form.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let (prompt, answer, detail) = match kind {
+            CardKind::GlyphToMeaning => (glyph, radical.meaning.clone(), Some(radical.pinyin.clone())),
+            CardKind::MeaningToGlyph => (radical.meaning.clone(), all_forms, Some(radical.pinyin.clone())),
+            CardKind::GlyphToPinyin => (glyph, radical.pinyin.clone(), Some(radical.meaning.clone())),
+        };
+        Card { id: CardId { radical: radical.number, kind }, prompt, answer, detail }
+    }
+
+    /// Every card of the given kinds, in radical order.
+    pub fn deck(radicals: &[Radical], kinds: &[CardKind]) -> Vec<Card> {
+        radicals.iter()
+            .flat_map(|radical| kinds.iter().map(|kind| Card::for_radical(radical, *kind)))
+            .collect()
+    }
+}
+
+/// One answered card, as kept in the review history.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReviewRecord {
+    pub card: CardId,
+    pub grade: Grade,
+    pub reviewed_at: Timestamp,
+    pub scheduler: SchedulerKind,
+}
+
+/// Cards to study now: those due for review, most overdue first, followed by
+/// up to `new_cards` that have never been reviewed.
+pub fn study_queue<'a>(
+    deck: &'a [Card],
+    states: &BTreeMap<CardId, CardState>,
+    now: Timestamp,
+    new_cards: usize,
+) -> Vec<&'a Card> {
+    let mut due = deck.iter()
+        .filter_map(|card| Some((card, states.get(&card.id)?)))
+        .filter(|(_, state)| state.is_due(now))
+        .collect::<Vec<_>>();
+    due.sort_by(|(_, a), (_, b)| a.due.total_cmp(&b.due));
+    let unseen = deck.iter()
+        .filter(|card| !states.contains_key(&card.id))
+        .take(new_cards);
+    due.into_iter().map(|(card, _)| card).chain(unseen).collect()
+}
+
+/// A run through a study queue. Cards answered `Again` come back at the end
+/// so the session only finishes once everything has been recalled.
+#[derive(Debug, Clone, Default)]
+pub struct StudySession {
+    queue: VecDeque<Card>,
+    reviewed: u32,
+    correct: u32,
+}
+
+impl StudySession {
+    pub fn new(cards: impl IntoIterator<Item = Card>) -> StudySession {
+        StudySession { queue: cards.into_iter().collect(), reviewed: 0, correct: 0 }
+    }
+
+    pub fn current(&self) -> Option<&Card> {
+        self.queue.front()
+    }
+
+    /// Records an answer to the current card and returns it.
+    pub fn answer(&mut self, grade: Grade) -> Option<Card> {
+        let card = self.queue.pop_front()?;
+        self.reviewed += 1;
+        if grade.is_correct() {
+            self.correct += 1;
+        } else {
+            self.queue.push_back(card.clone());
+        }
+        Some(card)
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    pub fn reviewed(&self) -> u32 {
+        self.reviewed
+    }
+
+    pub fn correct(&self) -> u32 {
+        self.correct
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scheduler::{Memory, DAY_MS};
+
+    const NOW: Timestamp = 1_700_000_000_000.0;
+
+    fn deck() -> Vec<Card> {
+        let radicals = Radical::parse_radicals(include_str!("../../public/radicals.csv")).unwrap();
+        Card::deck(&radicals[..5], &[CardKind::GlyphToMeaning])
+    }
+
+    fn due_at(due: Timestamp) -> CardState {
+        CardState { due, last_reviewed: due - DAY_MS, reviews: 1, lapses: 0, recalled: true, memory: Memory::Leitner { level: 1 } }
+    }
+
+    fn radicals_of(cards: &[&Card]) -> Vec<i32> {
+        cards.iter().map(|card| card.id.radical).collect()
+    }
+
+    #[test]
+    fn card_keys_round_trip() {
+        for kind in CardKind::ALL {
+            let id = CardId { radical: 9, kind };
+            assert_eq!(CardId::from_key(&id.key()), Some(id));
+        }
+        assert_eq!(CardId { radical: 9, kind: CardKind::GlyphToMeaning }.key(), "9/glyph_to_meaning");
+        for key in ["", "9", "nine/glyph_to_meaning", "9/", "9/meaning", "9/glyph_to_meaning/1", "/glyph_to_meaning"] {
+            assert_eq!(CardId::from_key(key), None, "{:?}", key);
+        }
+    }
+
+    #[test]
+    fn queues_overdue_cards_first_then_some_new_ones() {
+        let deck = deck();
+        let id = |index: usize| deck[index].id;
+        let states = BTreeMap::from([
+            (id(0), due_at(NOW - DAY_MS)),
+            (id(1), due_at(NOW - 3.0 * DAY_MS)),
+            (id(2), due_at(NOW + DAY_MS)),
+        ]);
+        assert_eq!(radicals_of(&study_queue(&deck, &states, NOW, 1)), [2, 1, 4]);
+        assert_eq!(radicals_of(&study_queue(&deck, &states, NOW, 5)), [2, 1, 4, 5]);
+        assert_eq!(radicals_of(&study_queue(&deck, &states, NOW, 0)), [2, 1]);
+        // A card due exactly now counts as due.
+        assert_eq!(radicals_of(&study_queue(&deck, &states, NOW + DAY_MS, 0)), [2, 1, 3]);
+        assert!(study_queue(&[], &states, NOW, 10).is_empty());
+    }
+
+    #[test]
+    fn sessions_requeue_forgotten_cards_until_recalled() {
+        let mut session = StudySession::new(deck().into_iter().take(3));
+        let mut answered = Vec::new();
+        for grade in [Grade::Again, Grade::Good, Grade::Hard, Grade::Again, Grade::Easy] {
+            answered.push(session.answer(grade).unwrap().id.radical);
+        }
+        assert_eq!(answered, [1, 2, 3, 1, 1]);
+        assert!(session.is_finished());
+        assert_eq!(session.current(), None);
+        assert_eq!(session.answer(Grade::Good), None);
+        assert_eq!((session.reviewed(), session.correct()), (5, 3));
+    }
+
+    #[test]
+    fn reports_progress_through_a_session() {
+        let mut session = StudySession::new(deck().into_iter().take(2));
+        assert_eq!(session.remaining(), 2);
+        assert_eq!(session.current().map(|card| card.id.radical), Some(1));
+        session.answer(Grade::Again);
+        assert_eq!(session.remaining(), 2);
+        assert_eq!(session.current().map(|card| card.id.radical), Some(2));
+        session.answer(Grade::Good);
+        assert_eq!(session.remaining(), 1);
+        assert!(!session.is_finished());
+    }
+
+    #[test]
+    fn builds_cards_for_each_kind() {
+        let radicals = Radical::parse_radicals(include_str!("../../public/radicals.csv")).unwrap();
+        let water = &radicals[84];
+        let card = |kind| Card::for_radical(water, kind);
+        assert_eq!((card(CardKind::GlyphToMeaning).prompt, card(CardKind::GlyphToMeaning).answer), ("水".to_string(), "water".to_string()));
+        assert_eq!(card(CardKind::MeaningToGlyph).answer, "水 氵 氺");
+        assert_eq!(card(CardKind::GlyphToPinyin).answer, "shuǐ");
+        assert_eq!(card(CardKind::GlyphToPinyin).detail.as_deref(), Some("water"));
+    }
+}
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+use super::error::{ZhongCharError, ZhongCharResult};
+
+/// A point on a stroke median, in the same coordinates as the outlines.
+pub type MedianPoint = (i32, i32);
+
+/// Stroke data for one character from Make Me a Hanzi's `graphics.txt`.
+///
+/// Coordinates are in a 1024-unit square with the y axis pointing up, so SVG
+/// output has to be flipped with [`CharacterGraphics::SVG_TRANSFORM`]. The
+/// `n`th stroke here is the `n`th entry of the dictionary's `matches`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CharacterGraphics {
+    pub character: char,
+    /// SVG path data outlining each stroke, in stroke order.
+    pub strokes: Vec<String>,
+    /// The line each stroke is drawn along, from where the brush starts to
+    /// where it lifts.
+    pub medians: Vec<Vec<MedianPoint>>,
+}
+
+impl CharacterGraphics {
+    pub const DATASET: &'static str = "graphics.txt";
+    /// Where the web app serves `DATASET` split into one file per character,
+    /// so a page only downloads the characters it draws.
+    pub const DIRECTORY: &'static str = "graphics";
+    pub const SVG_VIEW_BOX: &'static str = "0 0 1024 1024";
+    pub const SVG_TRANSFORM: &'static str = "scale(1, -1) translate(0, -900)";
+
+    /// Parses `graphics.txt`, one JSON object per line.
+    pub fn parse_graphics(text: &str) -> ZhongCharResult<Vec<CharacterGraphics>> {
+        let mut graphics: Vec<CharacterGraphics> = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() { continue };
+            let character = serde_json::from_str(line)
+                .map_err(|source| ZhongCharError::GraphicsLine { line: index + 1, source })?;
+            graphics.push(character);
+        }
+
+        Ok(graphics)
+    }
+
+    /// The file holding `character`'s graphics within `DIRECTORY`'s parent,
+    /// named by code point, e.g. `graphics/6c34.json` for 水.
+    pub fn file_name(character: char) -> String {
+        format!("{}/{:x}.json", Self::DIRECTORY, character as u32)
+    }
+
+    /// Parses one character's file: a single line of `graphics.txt`.
+    pub fn parse_character_graphics(text: &str) -> ZhongCharResult<CharacterGraphics> {
+        serde_json::from_str(text.trim()).map_err(|source| ZhongCharError::GraphicsLine { line: 1, source })
+    }
+
+    pub fn stroke_count(&self) -> usize {
+        self.strokes.len()
+    }
+
+    /// SVG path data for a stroke's median as a polyline.
+    pub fn median_path(&self, stroke: usize) -> Option<String> {
+        let median = self.medians.get(stroke)?;
+        let mut points = median.iter();
+        let (x, y) = points.next()?;
+        let mut path = format!("M {} {}", x, y);
+        for (x, y) in points {
+            path.push_str(&format!(" L {} {}", x, y));
+        }
+        Some(path)
+    }
+}
@@ -0,0 +1,264 @@
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+use super::error::IdsError;
+
+/// Placeholder Make Me a Hanzi uses for components it couldn't identify.
+pub const UNKNOWN_COMPONENT: char = '？';
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IdsOperator {
+    LeftToRight,
+    AboveToBelow,
+    LeftToMiddleAndRight,
+    AboveToMiddleAndBelow,
+    FullSurround,
+    SurroundFromAbove,
+    SurroundFromBelow,
+    SurroundFromLeft,
+    SurroundFromUpperLeft,
+    SurroundFromUpperRight,
+    SurroundFromLowerLeft,
+    Overlaid,
+    // Added in Unicode 15.1
+    SurroundFromRight,
+    SurroundFromLowerRight,
+    HorizontalReflection,
+    Rotation,
+    Subtraction,
+}
+
+impl IdsOperator {
+    pub fn from_char(c: char) -> Option<IdsOperator> {
+        let operator = match c {
+            '⿰' => IdsOperator::LeftToRight,
+            '⿱' => IdsOperator::AboveToBelow,
+            '⿲' => IdsOperator::LeftToMiddleAndRight,
+            '⿳' => IdsOperator::AboveToMiddleAndBelow,
+            '⿴' => IdsOperator::FullSurround,
+            '⿵' => IdsOperator::SurroundFromAbove,
+            '⿶' => IdsOperator::SurroundFromBelow,
+            '⿷' => IdsOperator::SurroundFromLeft,
+            '⿸' => IdsOperator::SurroundFromUpperLeft,
+            '⿹' => IdsOperator::SurroundFromUpperRight,
+            '⿺' => IdsOperator::SurroundFromLowerLeft,
+            '⿻' => IdsOperator::Overlaid,
+            '⿼' => IdsOperator::SurroundFromRight,
+            '⿽' => IdsOperator::SurroundFromLowerRight,
+            '⿾' => IdsOperator::HorizontalReflection,
+            '⿿' => IdsOperator::Rotation,
+            '㇯' => IdsOperator::Subtraction,
+            _ => return None,
+        };
+        Some(operator)
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            IdsOperator::LeftToRight => '⿰',
+            IdsOperator::AboveToBelow => '⿱',
+            IdsOperator::LeftToMiddleAndRight => '⿲',
+            IdsOperator::AboveToMiddleAndBelow => '⿳',
+            IdsOperator::FullSurround => '⿴',
+            IdsOperator::SurroundFromAbove => '⿵',
+            IdsOperator::SurroundFromBelow => '⿶',
+            IdsOperator::SurroundFromLeft => '⿷',
+            IdsOperator::SurroundFromUpperLeft => '⿸',
+            IdsOperator::SurroundFromUpperRight => '⿹',
+            IdsOperator::SurroundFromLowerLeft => '⿺',
+            IdsOperator::Overlaid => '⿻',
+            IdsOperator::SurroundFromRight => '⿼',
+            IdsOperator::SurroundFromLowerRight => '⿽',
+            IdsOperator::HorizontalReflection => '⿾',
+            IdsOperator::Rotation => '⿿',
+            IdsOperator::Subtraction => '㇯',
+        }
+    }
+
+    /// Number of operands the operator takes.
+    pub fn arity(self) -> usize {
+        match self {
+            IdsOperator::HorizontalReflection | IdsOperator::Rotation => 1,
+            IdsOperator::LeftToMiddleAndRight | IdsOperator::AboveToMiddleAndBelow => 3,
+            _ => 2,
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            IdsOperator::LeftToRight => "left to right",
+            IdsOperator::AboveToBelow => "above to below",
+            IdsOperator::LeftToMiddleAndRight => "left to middle and right",
+            IdsOperator::AboveToMiddleAndBelow => "above to middle and below",
+            IdsOperator::FullSurround => "full surround",
+            IdsOperator::SurroundFromAbove => "surround from above",
+            IdsOperator::SurroundFromBelow => "surround from below",
+            IdsOperator::SurroundFromLeft => "surround from left",
+            IdsOperator::SurroundFromUpperLeft => "surround from upper left",
+            IdsOperator::SurroundFromUpperRight => "surround from upper right",
+            IdsOperator::SurroundFromLowerLeft => "surround from lower left",
+            IdsOperator::Overlaid => "overlaid",
+            IdsOperator::SurroundFromRight => "surround from right",
+            IdsOperator::SurroundFromLowerRight => "surround from lower right",
+            IdsOperator::HorizontalReflection => "horizontal reflection",
+            IdsOperator::Rotation => "rotation",
+            IdsOperator::Subtraction => "subtraction",
+        }
+    }
+}
+
+/// A parsed Ideographic Description Sequence such as `⿰⿱白方攵`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum Decomposition {
+    Component(char),
+    Unknown,
+    Compound {
+        operator: IdsOperator,
+        components: Vec<Decomposition>,
+    },
+}
+
+impl Decomposition {
+    pub fn parse(ids: &str) -> Result<Decomposition, IdsError> {
+        let mut chars = ids.chars().enumerate().peekable();
+        if chars.peek().is_none() {
+            return Err(IdsError::Empty);
+        }
+        let decomposition = Decomposition::parse_node(&mut chars)?;
+        if let Some((position, found)) = chars.next() {
+            return Err(IdsError::TrailingComponent { position, found });
+        }
+        Ok(decomposition)
+    }
+
+    fn parse_node<I>(chars: &mut I) -> Result<Decomposition, IdsError>
+    where
+        I: Iterator<Item = (usize, char)>,
+    {
+        let Some((position, c)) = chars.next() else {
+            return Err(IdsError::Empty);
+        };
+        if c == UNKNOWN_COMPONENT {
+            return Ok(Decomposition::Unknown);
+        }
+        let Some(operator) = IdsOperator::from_char(c) else {
+            return Ok(Decomposition::Component(c));
+        };
+        let mut components = Vec::with_capacity(operator.arity());
+        for _ in 0..operator.arity() {
+            match Decomposition::parse_node(chars) {
+                Ok(component) => components.push(component),
+                Err(IdsError::Empty) => {
+                    return Err(IdsError::MissingOperand {
+                        operator: c,
+                        position,
+                        expected: operator.arity(),
+                        found: components.len(),
+                    });
+                }
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(Decomposition::Compound { operator, components })
+    }
+
+    /// Follows a path of child indices, as used by `Character::matches`.
+    pub fn get(&self, path: &[usize]) -> Option<&Decomposition> {
+        let Some((&first, rest)) = path.split_first() else {
+            return Some(self);
+        };
+        match self {
+            Decomposition::Compound { components, .. } => components.get(first)?.get(rest),
+            _ => None,
+        }
+    }
+
+    /// Leaf components in reading order, skipping unknown placeholders.
+    pub fn leaves(&self) -> Vec<char> {
+        match self {
+            Decomposition::Component(c) => vec![*c],
+            Decomposition::Unknown => Vec::new(),
+            Decomposition::Compound { components, .. } => {
+                components.iter().flat_map(|component| component.leaves()).collect()
+            }
+        }
+    }
+
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, Decomposition::Unknown)
+    }
+}
+
+impl FromStr for Decomposition {
+    type Err = IdsError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Decomposition::parse(s)
+    }
+}
+
+/// Writes the sequence back out in prefix notation, so `parse` then
+/// `to_string` returns the original string.
+impl fmt::Display for Decomposition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Decomposition::Component(c) => write!(f, "{}", c),
+            Decomposition::Unknown => write!(f, "{}", UNKNOWN_COMPONENT),
+            Decomposition::Compound { operator, components } => {
+                write!(f, "{}", operator.to_char())?;
+                for component in components {
+                    write!(f, "{}", component)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_round_trips_parse() {
+        for ids in ["木", "？", "⿰木木", "⿰⿱白方攵", "⿲彳丨亍", "⿱？心", "⿾⿰木口"] {
+            assert_eq!(Decomposition::parse(ids).unwrap().to_string(), ids);
+        }
+    }
+
+    #[test]
+    fn parses_nested_operators() {
+        let parsed = Decomposition::parse("⿰⿱白方攵").unwrap();
+        assert_eq!(parsed.leaves(), vec!['白', '方', '攵']);
+        assert_eq!(parsed.get(&[0, 1]), Some(&Decomposition::Component('方')));
+        assert_eq!(parsed.get(&[1, 0]), None);
+    }
+
+    #[test]
+    fn rejects_empty_sequences() {
+        assert_eq!(Decomposition::parse(""), Err(IdsError::Empty));
+    }
+
+    #[test]
+    fn reports_missing_operands() {
+        assert_eq!(
+            Decomposition::parse("⿰木"),
+            Err(IdsError::MissingOperand { operator: '⿰', position: 0, expected: 2, found: 1 }),
+        );
+        // The innermost incomplete operator is the one reported.
+        assert_eq!(
+            Decomposition::parse("⿱⿲木"),
+            Err(IdsError::MissingOperand { operator: '⿲', position: 1, expected: 3, found: 1 }),
+        );
+    }
+
+    #[test]
+    fn reports_trailing_components() {
+        assert_eq!(
+            Decomposition::parse("⿰木木口"),
+            Err(IdsError::TrailingComponent { position: 3, found: '口' }),
+        );
+        assert_eq!(Decomposition::parse("木口"), Err(IdsError::TrailingComponent { position: 1, found: '口' }));
+    }
+}
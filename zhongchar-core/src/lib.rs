@@ -0,0 +1,20 @@
+//! Data model and dataset parsing for 中 Char, kept free of any browser
+//! dependency so it can be built and tested natively.
+
+pub mod backup;
+pub mod character;
+pub mod error;
+pub mod export;
+pub mod flashcards;
+pub mod graphics;
+pub mod ids;
+pub mod pinyin;
+pub mod progress;
+pub mod quiz;
+mod radical_from_csv;
+pub mod radical;
+pub mod scheduler;
+pub mod script;
+pub mod search;
+pub mod tracing;
+pub mod validation;
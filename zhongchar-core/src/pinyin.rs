@@ -0,0 +1,321 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+use super::error::PinyinError;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Tone {
+    First,
+    Second,
+    Third,
+    Fourth,
+    Neutral,
+}
+
+impl Tone {
+    pub fn from_number(number: u32) -> Option<Tone> {
+        match number {
+            1 => Some(Tone::First),
+            2 => Some(Tone::Second),
+            3 => Some(Tone::Third),
+            4 => Some(Tone::Fourth),
+            0 | 5 => Some(Tone::Neutral),
+            _ => None,
+        }
+    }
+
+    /// 1–4 for the four tones and 5 for the neutral tone.
+    pub fn number(self) -> u32 {
+        match self {
+            Tone::First => 1,
+            Tone::Second => 2,
+            Tone::Third => 3,
+            Tone::Fourth => 4,
+            Tone::Neutral => 5,
+        }
+    }
+}
+
+/// Tone-marked letters and the plain letter and tone they stand for.
+const TONE_MARKED: &[(char, char, Tone)] = &[
+    ('ā', 'a', Tone::First), ('á', 'a', Tone::Second), ('ǎ', 'a', Tone::Third), ('à', 'a', Tone::Fourth),
+    ('ē', 'e', Tone::First), ('é', 'e', Tone::Second), ('ě', 'e', Tone::Third), ('è', 'e', Tone::Fourth),
+    ('ī', 'i', Tone::First), ('í', 'i', Tone::Second), ('ǐ', 'i', Tone::Third), ('ì', 'i', Tone::Fourth),
+    ('ō', 'o', Tone::First), ('ó', 'o', Tone::Second), ('ǒ', 'o', Tone::Third), ('ò', 'o', Tone::Fourth),
+    ('ū', 'u', Tone::First), ('ú', 'u', Tone::Second), ('ǔ', 'u', Tone::Third), ('ù', 'u', Tone::Fourth),
+    ('ǖ', 'ü', Tone::First), ('ǘ', 'ü', Tone::Second), ('ǚ', 'ü', Tone::Third), ('ǜ', 'ü', Tone::Fourth),
+    ('ế', 'ê', Tone::Second), ('ề', 'ê', Tone::Fourth),
+    ('ḿ', 'm', Tone::Second),
+    ('ń', 'n', Tone::Second), ('ň', 'n', Tone::Third), ('ǹ', 'n', Tone::Fourth),
+];
+
+/// Combining diacritics, for pinyin typed with decomposed characters.
+const COMBINING_TONE_MARKS: &[(char, Tone)] = &[
+    ('\u{0304}', Tone::First),
+    ('\u{0301}', Tone::Second),
+    ('\u{030C}', Tone::Third),
+    ('\u{0300}', Tone::Fourth),
+];
+
+/// Longest first so "zh" wins over "z".
+const INITIALS: &[&str] = &[
+    "zh", "ch", "sh",
+    "b", "p", "m", "f", "d", "t", "n", "l", "g", "k", "h", "j", "q", "x", "r", "z", "c", "s", "y", "w",
+];
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'ü' | 'ê')
+}
+
+/// A single pinyin syllable, stored as its toneless spelling plus a tone.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+pub struct Syllable {
+    letters: String,
+    tone: Tone,
+}
+
+impl Syllable {
+    /// Parses diacritic ("lǜ"), tone-number ("lv4", "lu:4", "lü4") or toneless
+    /// ("lü") spellings. A syllable without any tone is taken as neutral.
+    pub fn parse(text: &str) -> Result<Syllable, PinyinError> {
+        let text = text.trim().to_lowercase();
+        let mut chars: Vec<char> = text.chars().collect();
+        let mut tone = None;
+        if let Some(number) = chars.last().and_then(|c| c.to_digit(10)) {
+            tone = Some(Tone::from_number(number).ok_or(PinyinError::InvalidToneNumber(number))?);
+            chars.pop();
+        }
+        if chars.is_empty() {
+            return Err(PinyinError::Empty);
+        }
+
+        let mut letters = String::with_capacity(chars.len());
+        let set_tone = |new_tone: Tone, tone: &mut Option<Tone>| {
+            match tone {
+                Some(existing) if *existing != new_tone => Err(PinyinError::ConflictingTones(text.clone())),
+                _ => {
+                    *tone = Some(new_tone);
+                    Ok(())
+                }
+            }
+        };
+        let mut index = 0;
+        while index < chars.len() {
+            let c = chars[index];
+            index += 1;
+            if let Some(&(_, base, marked_tone)) = TONE_MARKED.iter().find(|(marked, _, _)| *marked == c) {
+                set_tone(marked_tone, &mut tone)?;
+                letters.push(base);
+            } else if let Some(&(_, marked_tone)) = COMBINING_TONE_MARKS.iter().find(|(mark, _)| *mark == c) {
+                set_tone(marked_tone, &mut tone)?;
+            } else if c == 'u' && chars.get(index) == Some(&':') {
+                index += 1;
+                letters.push('ü');
+            } else if c == 'v' {
+                letters.push('ü');
+            } else if c == '\u{0308}' && letters.ends_with('u') {
+                letters.pop();
+                letters.push('ü');
+            } else if c.is_ascii_lowercase() || c == 'ü' || c == 'ê' {
+                letters.push(c);
+            } else {
+                return Err(PinyinError::InvalidCharacter { syllable: text.clone(), found: c });
+            }
+        }
+
+        Ok(Syllable { letters, tone: tone.unwrap_or(Tone::Neutral) })
+    }
+
+    pub fn tone(&self) -> Tone {
+        self.tone
+    }
+
+    /// The initial consonant, or an empty string for syllables like "er" and
+    /// "ng". `y` and `w` are treated as initials.
+    pub fn initial(&self) -> &str {
+        if !self.letters.chars().any(is_vowel) {
+            return "";
+        }
+        INITIALS.iter()
+            .find(|initial| self.letters.starts_with(**initial))
+            .copied()
+            .unwrap_or("")
+    }
+
+    pub fn final_(&self) -> &str {
+        &self.letters[self.initial().len()..]
+    }
+
+    /// "yī"
+    pub fn to_diacritic(&self) -> String {
+        let Some(mark_index) = self.tone_mark_index() else {
+            return self.letters.clone();
+        };
+        self.letters.chars()
+            .enumerate()
+            .map(|(index, c)| {
+                if index != mark_index { return c };
+                TONE_MARKED.iter()
+                    .find(|(_, base, tone)| *base == c && *tone == self.tone)
+                    .map(|(marked, _, _)| *marked)
+                    .unwrap_or(c)
+            })
+            .collect()
+    }
+
+    /// "yi1", "lü4"
+    pub fn to_numbered(&self) -> String {
+        format!("{}{}", self.letters, self.tone.number())
+    }
+
+    /// "yi1", "lv4", for keyboards without ü.
+    pub fn to_ascii_numbered(&self) -> String {
+        format!("{}{}", self.letters.replace('ü', "v").replace('ê', "e"), self.tone.number())
+    }
+
+    /// "yi", "lü"
+    pub fn to_toneless(&self) -> &str {
+        &self.letters
+    }
+
+    /// Character index of the letter that carries the tone mark: `a` or `e`
+    /// if present, the `o` of `ou`, otherwise the last vowel. Syllabic `m`
+    /// and `n` carry the mark themselves.
+    fn tone_mark_index(&self) -> Option<usize> {
+        if self.tone == Tone::Neutral {
+            return None;
+        }
+        let chars: Vec<char> = self.letters.chars().collect();
+        let position = |target: char| chars.iter().position(|&c| c == target);
+        position('a')
+            .or_else(|| position('e'))
+            .or_else(|| position('ê'))
+            .or_else(|| self.letters.contains("ou").then(|| position('o')).flatten())
+            .or_else(|| chars.iter().rposition(|&c| is_vowel(c)))
+            .or_else(|| chars.iter().position(|&c| c == 'm' || c == 'n'))
+    }
+
+    /// Collation key: alphabetical with ü sorted after u, then by tone.
+    fn sort_key(&self) -> (String, Tone) {
+        (self.letters.replace('ü', "v"), self.tone)
+    }
+}
+
+impl Ord for Syllable {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key().cmp(&other.sort_key())
+    }
+}
+
+impl PartialOrd for Syllable {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl FromStr for Syllable {
+    type Err = PinyinError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Syllable::parse(s)
+    }
+}
+
+impl fmt::Display for Syllable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_diacritic())
+    }
+}
+
+/// Parses a reading field that may hold several alternatives, such as
+/// `"xǐ/xì"`, `"hǎn[1] "` or `"shàng shang"`. Bracketed footnotes are dropped.
+pub fn parse_readings(text: &str) -> Result<Vec<Syllable>, PinyinError> {
+    let mut without_footnotes = String::with_capacity(text.len());
+    let mut depth = 0u32;
+    for c in text.chars() {
+        match c {
+            '[' => depth += 1,
+            ']' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => without_footnotes.push(c),
+            _ => {}
+        }
+    }
+    without_footnotes
+        .split(|c: char| c.is_whitespace() || matches!(c, '/' | ',' | ';' | '\''))
+        .filter(|syllable| !syllable.is_empty())
+        .map(Syllable::parse)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn syllable(text: &str) -> Syllable {
+        Syllable::parse(text).unwrap()
+    }
+
+    #[test]
+    fn converts_tone_marks_to_numbers() {
+        assert_eq!(syllable("yī").to_numbered(), "yi1");
+        assert_eq!(syllable("lǜ").to_numbered(), "lü4");
+        assert_eq!(syllable("lǜ").to_ascii_numbered(), "lv4");
+        assert_eq!(syllable("Hǎo").to_numbered(), "hao3");
+        assert_eq!(syllable("ma").to_numbered(), "ma5");
+    }
+
+    #[test]
+    fn converts_numbers_to_tone_marks() {
+        assert_eq!(syllable("hao3").to_diacritic(), "hǎo");
+        assert_eq!(syllable("xue2").to_diacritic(), "xué");
+        assert_eq!(syllable("dou4").to_diacritic(), "dòu");
+        assert_eq!(syllable("gui4").to_diacritic(), "guì");
+        assert_eq!(syllable("ng2").to_diacritic(), "ńg");
+        assert_eq!(syllable("ma5").to_diacritic(), "ma");
+    }
+
+    #[test]
+    fn accepts_every_spelling_of_u_umlaut() {
+        for spelling in ["lv4", "lu:4", "lü4", "lǜ", "lu\u{0308}4", "lu\u{0308}\u{0300}"] {
+            assert_eq!(syllable(spelling), syllable("lǜ"), "{}", spelling);
+        }
+    }
+
+    #[test]
+    fn splits_initials_and_finals() {
+        let zhuang = syllable("zhuang4");
+        assert_eq!(zhuang.initial(), "zh");
+        assert_eq!(zhuang.final_(), "uang");
+        assert_eq!(syllable("er2").initial(), "");
+        assert_eq!(syllable("ng").initial(), "");
+    }
+
+    #[test]
+    fn rejects_malformed_syllables() {
+        assert_eq!(Syllable::parse(""), Err(PinyinError::Empty));
+        assert_eq!(Syllable::parse("5"), Err(PinyinError::Empty));
+        assert_eq!(Syllable::parse("ma6"), Err(PinyinError::InvalidToneNumber(6)));
+        assert_eq!(Syllable::parse("mǎ4"), Err(PinyinError::ConflictingTones("mǎ4".to_string())));
+        assert_eq!(
+            Syllable::parse("m-a"),
+            Err(PinyinError::InvalidCharacter { syllable: "m-a".to_string(), found: '-' }),
+        );
+    }
+
+    #[test]
+    fn parses_alternative_readings() {
+        let readings = parse_readings("xǐ/xì hǎn[1] ").unwrap();
+        let numbered = readings.iter().map(Syllable::to_numbered).collect::<Vec<_>>();
+        assert_eq!(numbered, ["xi3", "xi4", "han3"]);
+    }
+
+    #[test]
+    fn sorts_u_umlaut_after_u_and_then_by_tone() {
+        let mut syllables = [syllable("lü2"), syllable("lu4"), syllable("lu1"), syllable("la")];
+        syllables.sort();
+        let sorted = syllables.iter().map(Syllable::to_numbered).collect::<Vec<_>>();
+        assert_eq!(sorted, ["la5", "lu1", "lu4", "lü2"]);
+    }
+}
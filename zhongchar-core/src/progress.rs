@@ -0,0 +1,211 @@
+//! Study statistics computed from the flashcard review history.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::flashcards::{CardId, ReviewRecord};
+use crate::radical::Radical;
+use crate::scheduler::{CardState, Timestamp, DAY_MS};
+
+/// A card counts as learned once its scheduler is confident enough to leave
+/// it for about a week, and a radical once any of its cards is.
+pub const LEARNED_INTERVAL_DAYS: f64 = 6.0;
+
+/// Days since the Unix epoch in the learner's time zone. `local_offset_ms` is
+/// added to UTC to get local time, i.e. minus `Date.getTimezoneOffset()` in
+/// milliseconds.
+pub fn local_day(timestamp: Timestamp, local_offset_ms: f64) -> i64 {
+    ((timestamp + local_offset_ms) / DAY_MS).floor() as i64
+}
+
+/// Answers given on one day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DailyAccuracy {
+    /// As returned by [`local_day`].
+    pub day: i64,
+    pub correct: u32,
+    pub total: u32,
+}
+
+impl DailyAccuracy {
+    pub fn accuracy(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.correct as f64 / self.total as f64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StudyProgress {
+    /// Cards whose next review falls before the end of today.
+    pub due_today: usize,
+    pub radicals_learned: usize,
+    pub reviews: usize,
+    pub correct: usize,
+    /// The last `days` days, oldest first, including days without reviews.
+    pub daily: Vec<DailyAccuracy>,
+    /// Consecutive days with at least one review, ending today, or yesterday
+    /// if there hasn't been a review yet today.
+    pub current_streak: u32,
+    pub longest_streak: u32,
+}
+
+impl StudyProgress {
+    pub fn compute(
+        states: &BTreeMap<CardId, CardState>,
+        history: &[ReviewRecord],
+        now: Timestamp,
+        local_offset_ms: f64,
+        days: usize,
+    ) -> StudyProgress {
+        let today = local_day(now, local_offset_ms);
+        let due_today = states.values()
+            .filter(|state| local_day(state.due, local_offset_ms) <= today)
+            .count();
+        let radicals_learned = states.iter()
+            .filter(|(_, state)| (state.due - state.last_reviewed) / DAY_MS >= LEARNED_INTERVAL_DAYS)
+            .map(|(card, _)| card.radical)
+            .collect::<BTreeSet<_>>()
+            .len()
+            .min(Radical::COUNT as usize);
+
+        let mut by_day: BTreeMap<i64, DailyAccuracy> = BTreeMap::new();
+        for review in history {
+            let day = local_day(review.reviewed_at, local_offset_ms);
+            let entry = by_day.entry(day).or_insert(DailyAccuracy { day, correct: 0, total: 0 });
+            entry.total += 1;
+            entry.correct += u32::from(review.grade.is_correct());
+        }
+        let first_day = today - days as i64 + 1;
+        let daily = (first_day..=today)
+            .map(|day| by_day.get(&day).copied().unwrap_or(DailyAccuracy { day, correct: 0, total: 0 }))
+            .collect();
+
+        let mut longest_streak = 0;
+        let mut run = 0;
+        let mut previous_day = None;
+        for &day in by_day.keys() {
+            run = if previous_day == Some(day - 1) { run + 1 } else { 1 };
+            longest_streak = longest_streak.max(run);
+            previous_day = Some(day);
+        }
+        let current_streak = match previous_day {
+            Some(last) if last >= today - 1 => run,
+            _ => 0,
+        };
+
+        StudyProgress {
+            due_today,
+            radicals_learned,
+            reviews: history.len(),
+            correct: history.iter().filter(|review| review.grade.is_correct()).count(),
+            daily,
+            current_streak,
+            longest_streak,
+        }
+    }
+
+    pub fn radicals_remaining(&self) -> usize {
+        Radical::COUNT as usize - self.radicals_learned
+    }
+
+    pub fn accuracy(&self) -> Option<f64> {
+        (self.reviews > 0).then(|| self.correct as f64 / self.reviews as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flashcards::CardKind;
+    use crate::scheduler::{Grade, Memory, SchedulerKind};
+
+    const NOW: Timestamp = 1_700_000_000_000.0;
+
+    /// One review per entry, `days_ago` days before `NOW`.
+    fn history(days_ago: &[i64]) -> Vec<ReviewRecord> {
+        days_ago.iter()
+            .map(|&days| ReviewRecord {
+                card: CardId { radical: 1, kind: CardKind::GlyphToMeaning },
+                grade: if days % 2 == 0 { Grade::Good } else { Grade::Again },
+                reviewed_at: NOW - days as f64 * DAY_MS,
+                scheduler: SchedulerKind::Fsrs,
+            })
+            .collect()
+    }
+
+    fn progress(days_ago: &[i64]) -> StudyProgress {
+        StudyProgress::compute(&BTreeMap::new(), &history(days_ago), NOW, 0.0, 7)
+    }
+
+    #[test]
+    fn streaks_run_through_consecutive_days() {
+        let cases: [(&[i64], u32, u32); 6] = [
+            (&[], 0, 0),
+            (&[0], 1, 1),
+            (&[0, 0, 1, 2], 3, 3),
+            // Nothing yet today: yesterday's streak is still alive.
+            (&[1, 2, 3], 3, 3),
+            // A gap day ends the earlier run.
+            (&[0, 1, 3, 4, 5, 6], 2, 4),
+            // Two days without reviews breaks the current streak.
+            (&[2, 3], 0, 2),
+        ];
+        for (days_ago, current, longest) in cases {
+            let progress = progress(days_ago);
+            assert_eq!((progress.current_streak, progress.longest_streak), (current, longest), "{days_ago:?}");
+        }
+    }
+
+    #[test]
+    fn an_empty_history_has_empty_days() {
+        let progress = progress(&[]);
+        assert_eq!(progress.reviews, 0);
+        assert_eq!(progress.accuracy(), None);
+        assert_eq!(progress.daily.len(), 7);
+        assert!(progress.daily.iter().all(|day| day.total == 0 && day.accuracy() == 0.0));
+        assert_eq!(progress.daily.last().unwrap().day, local_day(NOW, 0.0));
+    }
+
+    #[test]
+    fn daily_accuracy_counts_correct_answers_per_day() {
+        let progress = progress(&[0, 0, 1, 10]);
+        assert_eq!(progress.reviews, 4);
+        assert_eq!(progress.correct, 3);
+        let today = progress.daily[6];
+        assert_eq!((today.correct, today.total), (2, 2));
+        let yesterday = progress.daily[5];
+        assert_eq!((yesterday.correct, yesterday.total), (0, 1));
+        // Older than the window, but still part of the totals.
+        assert_eq!(progress.daily.iter().map(|day| day.total).sum::<u32>(), 3);
+    }
+
+    #[test]
+    fn cards_are_due_and_learned_by_interval() {
+        let state = |due_in_days: f64, interval_days: f64| CardState {
+            due: NOW + due_in_days * DAY_MS,
+            last_reviewed: NOW + (due_in_days - interval_days) * DAY_MS,
+            reviews: 1,
+            lapses: 0,
+            recalled: true,
+            memory: Memory::Leitner { level: 1 },
+        };
+        let states = BTreeMap::from([
+            (CardId { radical: 1, kind: CardKind::GlyphToMeaning }, state(-1.0, 1.0)),
+            (CardId { radical: 1, kind: CardKind::MeaningToGlyph }, state(7.0, 7.0)),
+            (CardId { radical: 2, kind: CardKind::GlyphToMeaning }, state(10.0, 10.0)),
+            (CardId { radical: 3, kind: CardKind::GlyphToMeaning }, state(2.0, 2.0)),
+        ]);
+        let progress = StudyProgress::compute(&states, &[], NOW, 0.0, 7);
+        assert_eq!(progress.due_today, 1);
+        assert_eq!(progress.radicals_learned, 2);
+        assert_eq!(progress.radicals_remaining(), Radical::COUNT as usize - 2);
+    }
+
+    #[test]
+    fn days_follow_the_local_offset() {
+        let midnight = local_day(NOW, 0.0) as f64 * DAY_MS;
+        assert_eq!(local_day(midnight - 1.0, 0.0), local_day(NOW, 0.0) - 1);
+        assert_eq!(local_day(midnight - 1.0, 3_600_000.0), local_day(NOW, 0.0));
+    }
+}
@@ -0,0 +1,411 @@
+//! Quiz questions generated from the radical table.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::pinyin::{parse_readings, Syllable};
+use crate::radical::Radical;
+
+/// Wrong options offered alongside the right one.
+const DISTRACTORS: usize = 3;
+/// Radicals within this many strokes of the answer make plausible distractors.
+const SIMILAR_STROKES: i32 = 1;
+/// Words too common in meanings to make two radicals look alike.
+const MEANING_STOPWORDS: [&str; 8] = ["a", "an", "and", "of", "or", "the", "to", "small"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionKind {
+    /// Pick the radical with a given meaning.
+    RadicalForMeaning,
+    /// Type the radical's pinyin, tones included.
+    PinyinWithTones,
+    /// Pick the radical with a given Hán-Việt reading.
+    HanVietToGlyph,
+    /// Pick the radical with a given hangul name.
+    HangulToGlyph,
+}
+
+impl QuestionKind {
+    pub const ALL: [QuestionKind; 4] = [
+        QuestionKind::RadicalForMeaning,
+        QuestionKind::PinyinWithTones,
+        QuestionKind::HanVietToGlyph,
+        QuestionKind::HangulToGlyph,
+    ];
+
+    pub fn instruction(self) -> &'static str {
+        match self {
+            QuestionKind::RadicalForMeaning => "Which radical means",
+            QuestionKind::PinyinWithTones => "Type the pinyin, with tones, for",
+            QuestionKind::HanVietToGlyph => "Which radical is read in Hán-Việt as",
+            QuestionKind::HangulToGlyph => "Which radical is called in Korean",
+        }
+    }
+
+    /// The field of `radical` this kind asks about, if the radical has one.
+    fn prompt(self, radical: &Radical) -> Option<String> {
+        let prompt = match self {
+            QuestionKind::RadicalForMeaning => radical.meaning.trim().to_string(),
+            QuestionKind::PinyinWithTones => radical.radical_forms.primary.to_string(),
+            QuestionKind::HanVietToGlyph => radical.han_viet.trim().to_string(),
+            QuestionKind::HangulToGlyph => radical.hangul_romaja.native.trim().to_string(),
+        };
+        (!prompt.is_empty()).then_some(prompt)
+    }
+}
+
+impl fmt::Display for QuestionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuestionKind::RadicalForMeaning => write!(f, "Radical for a meaning"),
+            QuestionKind::PinyinWithTones => write!(f, "Pinyin with tones"),
+            QuestionKind::HanVietToGlyph => write!(f, "Hán-Việt reading"),
+            QuestionKind::HangulToGlyph => write!(f, "Hangul reading"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnswerFormat {
+    /// Radical glyphs to choose between.
+    Choice { options: Vec<String>, correct: usize },
+    /// Any of the radical's readings is accepted.
+    Typed { readings: Vec<Syllable> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Question {
+    /// The radical's Kangxi number.
+    pub radical: i32,
+    pub kind: QuestionKind,
+    pub prompt: String,
+    pub format: AnswerFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Outcome {
+    Correct,
+    /// The right syllable with the wrong tone.
+    WrongTone,
+    Wrong,
+}
+
+impl Question {
+    /// Builds a question of `kind` about `radicals[index]`, or `None` if the
+    /// radical lacks the data it needs.
+    pub fn generate(radicals: &[Radical], index: usize, kind: QuestionKind, rng: &mut QuizRng) -> Option<Question> {
+        let radical = radicals.get(index)?;
+        let prompt = kind.prompt(radical)?;
+        let format = match kind {
+            QuestionKind::PinyinWithTones => {
+                let readings = parse_readings(&radical.pinyin).ok().filter(|readings| !readings.is_empty())?;
+                AnswerFormat::Typed { readings }
+            }
+            _ => {
+                let mut options = distractors(radicals, index, kind, rng)
+                    .into_iter()
+                    .map(|distractor| distractor.radical_forms.primary.to_string())
+                    .collect::<Vec<_>>();
+                let correct = rng.below(options.len() + 1);
+                options.insert(correct, radical.radical_forms.primary.to_string());
+                AnswerFormat::Choice { options, correct }
+            }
+        };
+        Some(Question { radical: radical.number, kind, prompt, format })
+    }
+
+    pub fn grade(&self, response: &str) -> Outcome {
+        match &self.format {
+            AnswerFormat::Choice { options, correct } => {
+                if options[*correct] == response.trim() { Outcome::Correct } else { Outcome::Wrong }
+            }
+            AnswerFormat::Typed { readings } => {
+                let Ok(given) = parse_readings(response) else { return Outcome::Wrong };
+                let [given] = given.as_slice() else { return Outcome::Wrong };
+                if readings.contains(given) {
+                    Outcome::Correct
+                } else if readings.iter().any(|reading| reading.to_toneless() == given.to_toneless()) {
+                    Outcome::WrongTone
+                } else {
+                    Outcome::Wrong
+                }
+            }
+        }
+    }
+
+    pub fn correct_answer(&self) -> String {
+        match &self.format {
+            AnswerFormat::Choice { options, correct } => options[*correct].clone(),
+            AnswerFormat::Typed { readings } => {
+                readings.iter().map(|reading| reading.to_string()).collect::<Vec<_>>().join(" / ")
+            }
+        }
+    }
+}
+
+/// Wrong answers for a question about `radicals[index]`: radicals with a
+/// similar meaning first, then ones with about as many strokes, then any
+/// others. Radicals sharing the prompt are left out since they'd also be right.
+fn distractors<'a>(radicals: &'a [Radical], index: usize, kind: QuestionKind, rng: &mut QuizRng) -> Vec<&'a Radical> {
+    let answer = &radicals[index];
+    let prompt = kind.prompt(answer);
+    let answer_words = meaning_words(&answer.meaning);
+    let mut candidates = radicals.iter()
+        .enumerate()
+        .filter(|(other, radical)| *other != index && kind.prompt(radical).is_some() && kind.prompt(radical) != prompt)
+        .map(|(_, radical)| radical)
+        .collect::<Vec<_>>();
+    rng.shuffle(&mut candidates);
+    candidates.sort_by_key(|radical| {
+        if !meaning_words(&radical.meaning).is_disjoint(&answer_words) {
+            0
+        } else if (radical.stroke_count - answer.stroke_count).abs() <= SIMILAR_STROKES {
+            1
+        } else {
+            2
+        }
+    });
+    let mut seen = BTreeSet::from([answer.radical_forms.primary]);
+    candidates.retain(|radical| seen.insert(radical.radical_forms.primary));
+    candidates.truncate(DISTRACTORS);
+    candidates
+}
+
+fn meaning_words(meaning: &str) -> BTreeSet<String> {
+    meaning.split(|c: char| !c.is_alphabetic())
+        .map(|word| word.to_lowercase())
+        .filter(|word| !word.is_empty() && !MEANING_STOPWORDS.contains(&word.as_str()))
+        .collect()
+}
+
+/// A small xorshift generator so quizzes can be shuffled without pulling in a
+/// randomness crate. Seed it from the clock; it isn't for anything secret.
+#[derive(Debug, Clone)]
+pub struct QuizRng(u64);
+
+impl QuizRng {
+    pub fn new(seed: u64) -> QuizRng {
+        // Zero would get stuck at zero.
+        QuizRng(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+
+    /// A number in `0..bound`; `bound` must not be zero.
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuestionResult {
+    pub question: Question,
+    pub response: String,
+    pub outcome: Outcome,
+}
+
+/// A run of questions about randomly chosen radicals.
+#[derive(Debug, Clone, Default)]
+pub struct QuizSession {
+    questions: Vec<Question>,
+    results: Vec<QuestionResult>,
+}
+
+impl QuizSession {
+    /// Up to `length` questions, each about a different radical and of a kind
+    /// drawn from `kinds`.
+    pub fn generate(radicals: &[Radical], kinds: &[QuestionKind], length: usize, rng: &mut QuizRng) -> QuizSession {
+        let mut order = (0..radicals.len()).collect::<Vec<_>>();
+        rng.shuffle(&mut order);
+        let questions = if kinds.is_empty() {
+            Vec::new()
+        } else {
+            order.into_iter()
+                .filter_map(|index| {
+                    let kind = kinds[rng.below(kinds.len())];
+                    Question::generate(radicals, index, kind, rng)
+                })
+                .take(length)
+                .collect()
+        };
+        QuizSession { questions, results: Vec::new() }
+    }
+
+    pub fn current(&self) -> Option<&Question> {
+        self.questions.get(self.results.len())
+    }
+
+    /// Grades a response to the current question and moves on.
+    pub fn answer(&mut self, response: &str) -> Option<&QuestionResult> {
+        let question = self.current()?.clone();
+        let outcome = question.grade(response);
+        self.results.push(QuestionResult { question, response: response.trim().to_string(), outcome });
+        self.results.last()
+    }
+
+    /// The most recent result, for feedback before moving on.
+    pub fn last_result(&self) -> Option<&QuestionResult> {
+        self.results.last()
+    }
+
+    pub fn position(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.questions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.questions.is_empty()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.results.len() >= self.questions.len()
+    }
+
+    pub fn summary(&self) -> QuizSummary {
+        let mut by_kind: BTreeMap<QuestionKind, (usize, usize)> = BTreeMap::new();
+        for result in &self.results {
+            let (correct, total) = by_kind.entry(result.question.kind).or_default();
+            *total += 1;
+            if result.outcome == Outcome::Correct {
+                *correct += 1;
+            }
+        }
+        QuizSummary {
+            total: self.results.len(),
+            correct: self.results.iter().filter(|result| result.outcome == Outcome::Correct).count(),
+            by_kind,
+            missed: self.results.iter().filter(|result| result.outcome != Outcome::Correct).cloned().collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuizSummary {
+    pub total: usize,
+    pub correct: usize,
+    /// Correct answers and questions asked, per kind.
+    pub by_kind: BTreeMap<QuestionKind, (usize, usize)>,
+    pub missed: Vec<QuestionResult>,
+}
+
+impl QuizSummary {
+    pub fn accuracy(&self) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+        self.correct as f64 / self.total as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn radicals() -> Vec<Radical> {
+        Radical::parse_radicals(include_str!("../../public/radicals.csv")).unwrap()
+    }
+
+    fn water(radicals: &[Radical]) -> usize {
+        radicals.iter().position(|radical| radical.number == 85).unwrap()
+    }
+
+    #[test]
+    fn distractors_are_unique_and_never_the_answer() {
+        let radicals = radicals();
+        let mut rng = QuizRng::new(7);
+        for index in 0..radicals.len() {
+            for kind in [QuestionKind::RadicalForMeaning, QuestionKind::HanVietToGlyph, QuestionKind::HangulToGlyph] {
+                let Some(question) = Question::generate(&radicals, index, kind, &mut rng) else { continue };
+                let AnswerFormat::Choice { options, correct } = &question.format else { panic!("{kind} should be a choice") };
+                let answer = radicals[index].radical_forms.primary.to_string();
+                assert_eq!(options[*correct], answer);
+                assert_eq!(options.iter().filter(|option| **option == answer).count(), 1, "{options:?}");
+                assert_eq!(options.iter().collect::<BTreeSet<_>>().len(), options.len(), "{options:?}");
+                assert_eq!(options.len(), DISTRACTORS + 1);
+            }
+        }
+    }
+
+    #[test]
+    fn the_same_seed_gives_the_same_quiz() {
+        let radicals = radicals();
+        let quiz = |seed| QuizSession::generate(&radicals, &QuestionKind::ALL, 10, &mut QuizRng::new(seed)).questions;
+        assert_eq!(quiz(42), quiz(42));
+        assert_eq!(quiz(42).len(), 10);
+        assert_ne!(quiz(42), quiz(43));
+    }
+
+    #[test]
+    fn typed_pinyin_is_graded_by_syllable_and_tone() {
+        let radicals = radicals();
+        let question = Question::generate(&radicals, water(&radicals), QuestionKind::PinyinWithTones, &mut QuizRng::new(1)).unwrap();
+        assert_eq!(question.prompt, "水");
+        let cases = [
+            ("shui3", Outcome::Correct),
+            ("shuǐ", Outcome::Correct),
+            (" SHUI3 ", Outcome::Correct),
+            ("shui2", Outcome::WrongTone),
+            ("shuí", Outcome::WrongTone),
+            ("huo3", Outcome::Wrong),
+            ("shui3 shui3", Outcome::Wrong),
+            ("", Outcome::Wrong),
+        ];
+        for (response, outcome) in cases {
+            assert_eq!(question.grade(response), outcome, "{response:?}");
+        }
+        assert_eq!(question.correct_answer(), "shuǐ");
+    }
+
+    #[test]
+    fn summary_counts_correct_answers_per_kind() {
+        let radicals = radicals();
+        let mut session = QuizSession::generate(&radicals, &QuestionKind::ALL, 12, &mut QuizRng::new(5));
+        let mut expected: BTreeMap<QuestionKind, (usize, usize)> = BTreeMap::new();
+        let mut position = 0;
+        while let Some(question) = session.current().cloned() {
+            // Answer every other question right.
+            let right = position % 2 == 0;
+            let response = if right { question.correct_answer() } else { "x".to_string() };
+            let response = response.split(" / ").next().unwrap().to_string();
+            session.answer(&response);
+            let (correct, total) = expected.entry(question.kind).or_default();
+            *total += 1;
+            *correct += usize::from(right);
+            position += 1;
+        }
+        assert!(session.is_finished());
+        let summary = session.summary();
+        assert_eq!(summary.total, 12);
+        assert_eq!(summary.correct, 6);
+        assert_eq!(summary.missed.len(), 6);
+        assert_eq!(summary.by_kind, expected);
+        assert_eq!(summary.accuracy(), 0.5);
+    }
+
+    #[test]
+    fn a_quiz_without_kinds_is_empty() {
+        let session = QuizSession::generate(&radicals(), &[], 10, &mut QuizRng::new(1));
+        assert!(session.is_empty());
+        assert!(session.is_finished());
+        assert_eq!(session.summary().accuracy(), 0.0);
+    }
+}
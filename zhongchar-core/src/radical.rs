@@ -0,0 +1,446 @@
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+use std::fmt;
+use crate::character::Character;
+use crate::radical_from_csv;
+
+use super::error::{ZhongCharError, ZhongCharResult};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Radical {
+    pub number: i32,
+    pub radical_forms: RadicalForms,
+    pub stroke_count: i32,
+    pub meaning: String,
+    pub colloquial_term: Option<String>,
+    pub pinyin: String,
+    pub han_viet: String,
+    pub hiragana_romaji: Romanized,
+    pub hangul_romaja: Romanized,
+    pub frequency: i32,
+    pub simplified: Option<Simplification>,
+    pub examples: Vec<char>,
+    /// The examples column as written, e.g. `王、丁、七、三`.
+    pub examples_raw: String,
+}
+
+/// A radical together with the line of `radicals.csv` it came from.
+#[derive(Debug, Clone)]
+pub struct RadicalRow {
+    pub line: u64,
+    pub radical: Radical,
+}
+
+/// The glyphs a radical is written with, from a cell such as `丿 (乀、⺄)`:
+/// the first is the primary form and the parenthesised ones are variants.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RadicalForms {
+    pub primary: char,
+    pub variants: Vec<char>,
+    pub raw: String,
+}
+
+impl RadicalForms {
+    /// Returns `None` if the cell holds no glyph at all.
+    pub fn parse(raw: &str) -> Option<RadicalForms> {
+        let mut glyphs = raw.chars().filter(|&c| is_glyph(c));
+        let primary = glyphs.next()?;
+        Some(RadicalForms {
+            primary,
+            variants: glyphs.collect(),
+            raw: raw.trim().to_string(),
+        })
+    }
+
+    /// The primary form followed by the variants.
+    pub fn all(&self) -> impl Iterator<Item = char> + '_ {
+        std::iter::once(self.primary).chain(self.variants.iter().copied())
+    }
+
+    pub fn contains(&self, glyph: char) -> bool {
+        self.all().any(|form| form == glyph)
+    }
+}
+
+impl fmt::Display for RadicalForms {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+/// A native-script name paired with its romanization, from cells such as
+/// `いち / ichi` or `마음심 (심방변 / 마음심밑) / maeumsim (simbangbyeon / maeumsimmit)`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Romanized {
+    pub native: String,
+    pub romanized: String,
+    pub raw: String,
+}
+
+impl Romanized {
+    /// Splits on the first `/` outside parentheses, since alternative names
+    /// inside them use `/` too. A cell without one is all native script.
+    pub fn parse(raw: &str) -> Romanized {
+        let mut depth = 0usize;
+        let separator = raw.char_indices().find(|&(_, c)| {
+            match c {
+                '(' => depth += 1,
+                ')' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            c == '/' && depth == 0
+        });
+        let (native, romanized) = match separator {
+            Some((index, _)) => (&raw[..index], &raw[index + 1..]),
+            None => (raw, ""),
+        };
+        Romanized {
+            native: native.trim().to_string(),
+            romanized: romanized.trim().to_string(),
+            raw: raw.trim().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Romanized {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+/// What the `Simplified` column says about a radical.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum Simplification {
+    /// A plain glyph such as `纟`: the form the radical takes in simplified
+    /// Chinese.
+    SimplifiedForm(char),
+    /// `(pr. 兒)`: the radical itself is the PRC simplification of these
+    /// traditional characters.
+    SimplifiesFrom(Vec<char>),
+}
+
+impl Simplification {
+    pub fn parse(raw: &str) -> Option<Simplification> {
+        let raw = raw.trim();
+        if let Some(traditional) = raw.strip_prefix("(pr.").and_then(|rest| rest.strip_suffix(')')) {
+            let traditional: Vec<char> = traditional.chars().filter(|&c| is_glyph(c)).collect();
+            return (!traditional.is_empty()).then_some(Simplification::SimplifiesFrom(traditional));
+        }
+        let mut chars = raw.chars();
+        match (chars.next(), chars.next()) {
+            (Some(form), None) if is_glyph(form) => Some(Simplification::SimplifiedForm(form)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Simplification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Simplification::SimplifiedForm(form) => write!(f, "{}", form),
+            Simplification::SimplifiesFrom(traditional) => {
+                let traditional: Vec<String> = traditional.iter().map(char::to_string).collect();
+                write!(f, "(pr. {})", traditional.join("、"))
+            }
+        }
+    }
+}
+
+/// Example characters from a `、`-separated cell such as `王、丁、七、三`.
+fn parse_examples(raw: &str) -> Vec<char> {
+    raw.split('、')
+        .flat_map(|example| example.trim().chars())
+        .filter(|&c| is_glyph(c))
+        .collect()
+}
+
+/// Anything but ASCII, whitespace and the `、` list separator. Forms from the
+/// CJK Radicals Supplement such as ⺄ are symbols rather than letters to
+/// Unicode, so `char::is_alphabetic` would drop them.
+fn is_glyph(c: char) -> bool {
+    !c.is_ascii() && !c.is_whitespace() && c != '、'
+}
+
+impl Radical {
+    pub const DATASET: &'static str = "radicals.csv";
+
+    /// The number of Kangxi radicals, and so the highest radical number.
+    pub const COUNT: i32 = 214;
+
+    /// Parses the tab-separated `radicals.csv`.
+    pub fn parse_radicals(text: &str) -> ZhongCharResult<Vec<Radical>> {
+        let rows = Radical::parse_radical_rows(text)?;
+        Ok(rows.into_iter().map(|row| row.radical).collect())
+    }
+
+    /// Like [`Radical::parse_radicals`], but keeps the line each radical was
+    /// read from so problems can be reported against the file.
+    pub fn parse_radical_rows(text: &str) -> ZhongCharResult<Vec<RadicalRow>> {
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(b'\t')
+            .from_reader(text.as_bytes());
+        let headers = reader.headers()?.clone();
+        let mut rows: Vec<RadicalRow> = Vec::new();
+        for result in reader.records() {
+            let record = result?;
+            let line = record.position().map(|position| position.line()).unwrap_or_default();
+            let radical: radical_from_csv::Radical = record.deserialize(Some(&headers))
+                .map_err(|error| field_error(&headers, line, error))?;
+            let radical = Radical::try_from(radical)
+                .map_err(|error| ZhongCharError::CsvField {
+                    line,
+                    column: error.column.to_string(),
+                    message: error.message,
+                })?;
+            rows.push(RadicalRow { line, radical });
+        }
+
+        Ok(rows)
+    }
+
+    /// Whether the dictionary could file a character under this radical as
+    /// `glyph`: one of its forms, or its simplified form, as with 讠 for 言.
+    pub fn is_written_as(&self, glyph: char) -> bool {
+        self.radical_forms.contains(glyph) || self.simplified == Some(Simplification::SimplifiedForm(glyph))
+    }
+
+    /// Characters filed under any of this radical's forms, keyed by the
+    /// number of strokes left once the radical itself is removed.
+    pub fn characters_by_residual_strokes(&self, dictionary: &[Character]) -> BTreeMap<usize, Vec<Character>> {
+        let form_stroke_counts: Vec<(char, usize)> = self.radical_forms.all()
+            .map(|form| {
+                let stroke_count = dictionary.iter()
+                    .find(|character| character.character == form)
+                    .map(|character| character.stroke_count())
+                    .unwrap_or(self.stroke_count as usize);
+                (form, stroke_count)
+            })
+            .collect();
+        let mut index: BTreeMap<usize, Vec<Character>> = BTreeMap::new();
+        for character in dictionary {
+            let Some(&(_, form_stroke_count)) = form_stroke_counts.iter()
+                .find(|(form, _)| *form == character.radical) else { continue };
+            if self.radical_forms.contains(character.character) { continue };
+            let residual = character.stroke_count().saturating_sub(form_stroke_count);
+            index.entry(residual).or_default().push(character.clone());
+        }
+        index
+    }
+
+}
+
+/// Names the offending column when a cell fails to deserialize; errors in
+/// the record as a whole are passed through unchanged.
+fn field_error(headers: &csv::StringRecord, line: u64, error: csv::Error) -> ZhongCharError {
+    let csv::ErrorKind::Deserialize { err, .. } = error.kind() else {
+        return ZhongCharError::Csv(error);
+    };
+    let Some(column) = err.field().and_then(|index| headers.get(index as usize)) else {
+        return ZhongCharError::Csv(error);
+    };
+    ZhongCharError::CsvField {
+        line,
+        column: column.to_string(),
+        message: err.kind().to_string(),
+    }
+}
+
+impl TryFrom<radical_from_csv::Radical> for Radical {
+    type Error = radical_from_csv::FieldError;
+
+    fn try_from(value: radical_from_csv::Radical) -> Result<Self, Self::Error> {
+        let radical_forms = RadicalForms::parse(&value.radical_forms)
+            .ok_or_else(|| radical_from_csv::FieldError {
+                column: "Radical forms",
+                message: "no glyph".to_string(),
+            })?;
+        let simplified = value.simplified
+            .filter(|simplified| !simplified.trim().is_empty())
+            .map(|simplified| Simplification::parse(&simplified).ok_or_else(|| radical_from_csv::FieldError {
+                column: "Simplified",
+                message: format!("expected a glyph or \"(pr. …)\", found {:?}", simplified),
+            }))
+            .transpose()?;
+        Ok(Self {
+            radical_forms,
+            number: value.number,
+            stroke_count: value.stroke_count,
+            meaning: value.meaning,
+            colloquial_term: value.colloquial_term,
+            pinyin: value.pinyin,
+            han_viet: value.han_viet,
+            hiragana_romaji: Romanized::parse(&value.hiragana_romaji),
+            hangul_romaja: Romanized::parse(&value.hangul_romaja),
+            frequency: value.frequency,
+            simplified,
+            examples: parse_examples(&value.examples),
+            examples_raw: value.examples.trim().to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "#\tRadical forms\tStroke count\tMeaning\tColloquial Term\tPīnyīn\tHán-Việt\tHiragana-Romaji\tHangul-Romaja\tFrequency\tSimplified\tExamples";
+
+    fn csv(rows: &[&str]) -> String {
+        std::iter::once(HEADER).chain(rows.iter().copied()).collect::<Vec<_>>().join("\n")
+    }
+
+    #[test]
+    fn parses_rows_natively() {
+        let text = csv(&[
+            "1\t一\t1\tone\t一字旁\tyī\tnhất\tいち / ichi\t한일 / hanil\t42\t\t王、丁、七、三",
+            "120\t糸 (糹、纟)\t6\tsilk\t绞丝旁\tmì\tmịch\tいと / ito\t실사 / silsa\t823\t纟\t紅、紙",
+        ]);
+        let rows = Radical::parse_radical_rows(&text).unwrap();
+        assert_eq!(rows.iter().map(|row| row.line).collect::<Vec<_>>(), [2, 3]);
+        let silk = &rows[1].radical;
+        assert_eq!(silk.number, 120);
+        assert_eq!(silk.stroke_count, 6);
+        assert_eq!(silk.colloquial_term.as_deref(), Some("绞丝旁"));
+        assert_eq!(silk.simplified, Some(Simplification::SimplifiedForm('纟')));
+        assert_eq!(rows[0].radical.simplified, None);
+    }
+
+    #[test]
+    fn parses_the_shipped_radical_table() {
+        let radicals = Radical::parse_radicals(include_str!("../../public/radicals.csv")).unwrap();
+        assert_eq!(radicals.len(), Radical::COUNT as usize);
+        assert!(radicals.iter().zip(1..).all(|(radical, number)| radical.number == number));
+    }
+
+    #[test]
+    fn reports_the_line_and_column_of_bad_cells() {
+        let cases = [
+            ("1\t一\tone\tone\t\tyī\tnhất\tいち / ichi\t한일 / hanil\t42\t\t王", "Stroke count"),
+            ("1\tone\t1\tone\t\tyī\tnhất\tいち / ichi\t한일 / hanil\t42\t\t王", "Radical forms"),
+            ("1\t一\t1\tone\t\tyī\tnhất\tいち / ichi\t한일 / hanil\t42\tsimple\t王", "Simplified"),
+        ];
+        for (row, expected) in cases {
+            let text = csv(&["2\t丨\t1\tline\t\tgǔn\tcổn\tぼう / bō\t뚫을곤 / ddulheulgon\t21\t\t十", row]);
+            match Radical::parse_radicals(&text) {
+                Err(ZhongCharError::CsvField { line, column, .. }) => {
+                    assert_eq!((line, column.as_str()), (3, expected), "{}", row);
+                }
+                other => panic!("expected a field error for {:?}, got {:?}", row, other),
+            }
+        }
+    }
+
+    #[test]
+    fn parses_radical_forms() {
+        let cases = [
+            ("一", Some(('一', vec![]))),
+            ("丿 (乀、⺄)", Some(('丿', vec!['乀', '⺄']))),
+            (" 水 (氵、氺) ", Some(('水', vec!['氵', '氺']))),
+            ("人(亻)", Some(('人', vec!['亻']))),
+            ("", None),
+            ("( 、 )", None),
+        ];
+        for (raw, expected) in cases {
+            let parsed = RadicalForms::parse(raw);
+            assert_eq!(parsed.clone().map(|forms| (forms.primary, forms.variants)), expected, "{:?}", raw);
+            if let Some(forms) = parsed {
+                assert_eq!(forms.to_string(), raw.trim());
+            }
+        }
+        let forms = RadicalForms::parse("丿 (乀、⺄)").unwrap();
+        assert_eq!(forms.all().collect::<Vec<_>>(), ['丿', '乀', '⺄']);
+        assert!(forms.contains('⺄') && !forms.contains('一'));
+    }
+
+    #[test]
+    fn parses_romanized_names() {
+        let cases = [
+            ("いち / ichi", "いち", "ichi"),
+            ("마음심 (심방변 / 마음심밑) / maeumsim (simbangbyeon / maeumsimmit)", "마음심 (심방변 / 마음심밑)", "maeumsim (simbangbyeon / maeumsimmit)"),
+            ("(아래) 물수 (삼수변) / (arae) mulsu (samsubyeon)", "(아래) 물수 (삼수변)", "(arae) mulsu (samsubyeon)"),
+            ("かめ", "かめ", ""),
+            ("かめ / ", "かめ", ""),
+            ("", "", ""),
+            // An unbalanced parenthesis hides every later separator.
+            ("まる (丸 / maru", "まる (丸 / maru", ""),
+        ];
+        for (raw, native, romanized) in cases {
+            let parsed = Romanized::parse(raw);
+            assert_eq!((parsed.native.as_str(), parsed.romanized.as_str()), (native, romanized), "{:?}", raw);
+            assert_eq!(parsed.to_string(), raw.trim());
+        }
+    }
+
+    #[test]
+    fn parses_simplifications() {
+        let cases = [
+            ("纟", Some(Simplification::SimplifiedForm('纟'))),
+            (" 页 ", Some(Simplification::SimplifiedForm('页'))),
+            ("(pr. 兒)", Some(Simplification::SimplifiesFrom(vec!['兒']))),
+            ("(pr. 乾、幹)", Some(Simplification::SimplifiesFrom(vec!['乾', '幹']))),
+            ("(pr. )", None),
+            ("(pr. 兒", None),
+            ("纟页", None),
+            ("x", None),
+            ("", None),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(Simplification::parse(raw), expected, "{:?}", raw);
+        }
+        assert_eq!(Simplification::SimplifiesFrom(vec!['乾', '幹']).to_string(), "(pr. 乾、幹)");
+    }
+
+    #[test]
+    fn parses_examples() {
+        let cases: [(&str, &[char]); 5] = [
+            ("王、丁、七、三", &['王', '丁', '七', '三']),
+            (" 王 、 丁 ", &['王', '丁']),
+            ("王丁", &['王', '丁']),
+            ("王、、x、1", &['王']),
+            ("", &[]),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(parse_examples(raw), expected, "{:?}", raw);
+        }
+    }
+
+    fn character(line: &str) -> Character {
+        Character::parse_dictionary(line).unwrap().remove(0)
+    }
+
+    #[test]
+    fn indexes_characters_by_residual_strokes() {
+        let dictionary = [
+            r#"{"character":"人","pinyin":["rén"],"decomposition":"？","radical":"人","matches":[null,null]}"#,
+            r#"{"character":"亻","pinyin":["rén"],"decomposition":"？","radical":"亻","matches":[null,null]}"#,
+            r#"{"character":"从","pinyin":["cóng"],"decomposition":"⿰人人","radical":"人","matches":[[0],[0],[1],[1]]}"#,
+            r#"{"character":"仁","pinyin":["rén"],"decomposition":"⿰亻二","radical":"亻","matches":[[0],[0],[1],[1]]}"#,
+            r#"{"character":"休","pinyin":["xiū"],"decomposition":"⿰亻木","radical":"亻","matches":[[0],[0],[1],[1],[1],[1]]}"#,
+            r#"{"character":"木","pinyin":["mù"],"decomposition":"？","radical":"木","matches":[null,null,null,null]}"#,
+        ].map(character);
+        let radicals = Radical::parse_radicals(include_str!("../../public/radicals.csv")).unwrap();
+        let person = radicals.iter().find(|radical| radical.number == 9).unwrap();
+        let index = person.characters_by_residual_strokes(&dictionary);
+        let index = index.into_iter()
+            .map(|(residual, characters)| (residual, characters.into_iter().map(|character| character.character).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        assert_eq!(index, [(2, vec!['从', '仁']), (4, vec!['休'])]);
+        // Forms missing from the dictionary fall back to the radical's own stroke count.
+        assert_eq!(person.characters_by_residual_strokes(&dictionary[2..]).keys().collect::<Vec<_>>(), [&2, &4]);
+        assert!(person.characters_by_residual_strokes(&[]).is_empty());
+    }
+
+    #[test]
+    fn recognises_forms_and_simplified_forms() {
+        let radicals = Radical::parse_radicals(include_str!("../../public/radicals.csv")).unwrap();
+        let written_as = |glyph: char| radicals.iter().find(|radical| radical.is_written_as(glyph)).map(|radical| radical.number);
+        assert_eq!(written_as('言'), Some(149));
+        assert_eq!(written_as('訁'), Some(149));
+        assert_eq!(written_as('讠'), Some(149));
+        assert_eq!(written_as('氵'), Some(85));
+        assert_eq!(written_as('儿'), Some(10));
+        assert_eq!(written_as('兒'), None);
+        assert_eq!(written_as('好'), None);
+    }
+}
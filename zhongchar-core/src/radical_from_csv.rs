@@ -26,4 +26,11 @@ pub struct Radical {
     pub simplified: Option<String>,
     #[serde(rename = "Examples")]
     pub examples: String,
-}
\ No newline at end of file
+}
+
+/// A cell that deserialized but doesn't make sense, reported against the
+/// column header so the caller can add the line.
+pub struct FieldError {
+    pub column: &'static str,
+    pub message: String,
+}
@@ -0,0 +1,459 @@
+//! Spaced-repetition schedulers deciding when a flashcard is next due.
+//!
+//! Each algorithm implements [`Scheduler`]; [`SchedulerKind`] names them for
+//! settings and dispatches to the implementation.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Milliseconds since the Unix epoch, as from `Date.now()`.
+pub type Timestamp = f64;
+
+pub const DAY_MS: f64 = 86_400_000.0;
+
+/// How well the learner recalled a card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Grade {
+    Again,
+    Hard,
+    Good,
+    Easy,
+}
+
+impl Grade {
+    pub const ALL: [Grade; 4] = [Grade::Again, Grade::Hard, Grade::Good, Grade::Easy];
+
+    pub fn is_correct(self) -> bool {
+        self != Grade::Again
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Grade::Again => "Again",
+            Grade::Hard => "Hard",
+            Grade::Good => "Good",
+            Grade::Easy => "Easy",
+        }
+    }
+}
+
+/// What a scheduler remembers about a card between reviews. Each algorithm
+/// keeps its own model; a card last scheduled by a different algorithm is
+/// treated as new by the current one, though its review counts carry over.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "algorithm", rename_all = "snake_case")]
+pub enum Memory {
+    Sm2 { ease: f64, interval_days: f64, repetitions: u32 },
+    Fsrs { stability: f64, difficulty: f64 },
+    Leitner { level: u8 },
+}
+
+/// Scheduling state of one card.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CardState {
+    pub due: Timestamp,
+    pub last_reviewed: Timestamp,
+    pub reviews: u32,
+    /// Times the card was forgotten after first being learned.
+    pub lapses: u32,
+    /// Whether the latest answer was correct. Only forgetting a card that
+    /// was recalled last time counts as a lapse.
+    pub recalled: bool,
+    pub memory: Memory,
+}
+
+impl CardState {
+    fn after_review(previous: Option<&CardState>, grade: Grade, now: Timestamp, memory: Memory, interval_days: f64) -> CardState {
+        let reviews = previous.map_or(0, |previous| previous.reviews);
+        let lapses = previous.map_or(0, |previous| previous.lapses);
+        CardState {
+            due: now + interval_days * DAY_MS,
+            last_reviewed: now,
+            reviews: reviews + 1,
+            lapses: lapses + u32::from(previous.is_some_and(|previous| previous.recalled) && !grade.is_correct()),
+            recalled: grade.is_correct(),
+            memory,
+        }
+    }
+
+    pub fn is_due(&self, now: Timestamp) -> bool {
+        self.due <= now
+    }
+
+    /// Days since the last review, as a fraction.
+    pub fn elapsed_days(&self, now: Timestamp) -> f64 {
+        ((now - self.last_reviewed) / DAY_MS).max(0.0)
+    }
+}
+
+/// A spaced-repetition algorithm.
+pub trait Scheduler {
+    fn kind(&self) -> SchedulerKind;
+
+    /// The card's state after answering it with `grade` at `now`.
+    /// `previous` is `None` for a card never reviewed before.
+    fn review(&self, previous: Option<&CardState>, grade: Grade, now: Timestamp) -> CardState;
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SchedulerKind {
+    Sm2,
+    #[default]
+    Fsrs,
+    Leitner,
+}
+
+impl SchedulerKind {
+    pub const ALL: [SchedulerKind; 3] = [SchedulerKind::Sm2, SchedulerKind::Fsrs, SchedulerKind::Leitner];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            SchedulerKind::Sm2 => "sm2",
+            SchedulerKind::Fsrs => "fsrs",
+            SchedulerKind::Leitner => "leitner",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<SchedulerKind> {
+        SchedulerKind::ALL.into_iter().find(|kind| kind.key() == key)
+    }
+
+    pub fn scheduler(self) -> Box<dyn Scheduler> {
+        match self {
+            SchedulerKind::Sm2 => Box::new(Sm2),
+            SchedulerKind::Fsrs => Box::new(Fsrs::default()),
+            SchedulerKind::Leitner => Box::new(Leitner),
+        }
+    }
+}
+
+impl fmt::Display for SchedulerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchedulerKind::Sm2 => write!(f, "SM-2"),
+            SchedulerKind::Fsrs => write!(f, "FSRS"),
+            SchedulerKind::Leitner => write!(f, "Leitner"),
+        }
+    }
+}
+
+/// The SuperMemo 2 algorithm, with the four grades mapped onto its 0–5
+/// quality scale as 1, 3, 4 and 5.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sm2;
+
+impl Sm2 {
+    const INITIAL_EASE: f64 = 2.5;
+    const MIN_EASE: f64 = 1.3;
+}
+
+impl Scheduler for Sm2 {
+    fn kind(&self) -> SchedulerKind {
+        SchedulerKind::Sm2
+    }
+
+    fn review(&self, previous: Option<&CardState>, grade: Grade, now: Timestamp) -> CardState {
+        let (ease, interval_days, repetitions) = match previous.map(|previous| previous.memory) {
+            Some(Memory::Sm2 { ease, interval_days, repetitions }) => (ease, interval_days, repetitions),
+            _ => (Sm2::INITIAL_EASE, 0.0, 0),
+        };
+        let quality = match grade {
+            Grade::Again => 1.0,
+            Grade::Hard => 3.0,
+            Grade::Good => 4.0,
+            Grade::Easy => 5.0,
+        };
+        let ease = (ease + 0.1 - (5.0 - quality) * (0.08 + (5.0 - quality) * 0.02)).max(Sm2::MIN_EASE);
+        let (interval_days, repetitions) = if !grade.is_correct() {
+            (1.0, 0)
+        } else {
+            let interval = match repetitions {
+                0 => 1.0,
+                1 => 6.0,
+                _ => (interval_days * ease).round(),
+            };
+            (interval, repetitions + 1)
+        };
+        let memory = Memory::Sm2 { ease, interval_days, repetitions };
+        CardState::after_review(previous, grade, now, memory, interval_days)
+    }
+}
+
+/// FSRS-4.5, the Free Spaced Repetition Scheduler, with its published
+/// default weights.
+#[derive(Debug, Clone, Copy)]
+pub struct Fsrs {
+    pub weights: [f64; 17],
+    /// Probability of recall to schedule the next review at.
+    pub desired_retention: f64,
+    pub maximum_interval_days: f64,
+}
+
+impl Default for Fsrs {
+    fn default() -> Self {
+        Fsrs {
+            weights: [
+                0.4872, 1.4003, 3.7145, 13.8206, 5.1618, 1.2298, 0.8975, 0.031, 1.6474, 0.1367, 1.0461, 2.1072,
+                0.0793, 0.3246, 1.587, 0.2272, 2.8755,
+            ],
+            desired_retention: 0.9,
+            maximum_interval_days: 36500.0,
+        }
+    }
+}
+
+impl Fsrs {
+    const DECAY: f64 = -0.5;
+    const FACTOR: f64 = 19.0 / 81.0;
+
+    fn grade_value(grade: Grade) -> f64 {
+        match grade {
+            Grade::Again => 1.0,
+            Grade::Hard => 2.0,
+            Grade::Good => 3.0,
+            Grade::Easy => 4.0,
+        }
+    }
+
+    /// Probability of recalling a card of `stability` after `elapsed_days`.
+    pub fn retrievability(elapsed_days: f64, stability: f64) -> f64 {
+        (1.0 + Fsrs::FACTOR * elapsed_days / stability).powf(Fsrs::DECAY)
+    }
+
+    fn initial_difficulty(&self, grade: f64) -> f64 {
+        (self.weights[4] - (grade - 3.0) * self.weights[5]).clamp(1.0, 10.0)
+    }
+
+    fn next_difficulty(&self, difficulty: f64, grade: f64) -> f64 {
+        let difficulty = difficulty - self.weights[6] * (grade - 3.0);
+        // Mean reversion towards the difficulty of a card first rated Easy.
+        let reverted = self.weights[7] * self.initial_difficulty(4.0) + (1.0 - self.weights[7]) * difficulty;
+        reverted.clamp(1.0, 10.0)
+    }
+
+    fn recall_stability(&self, difficulty: f64, stability: f64, retrievability: f64, grade: Grade) -> f64 {
+        let w = &self.weights;
+        let hard_penalty = if grade == Grade::Hard { w[15] } else { 1.0 };
+        let easy_bonus = if grade == Grade::Easy { w[16] } else { 1.0 };
+        stability
+            * (w[8].exp()
+                * (11.0 - difficulty)
+                * stability.powf(-w[9])
+                * ((w[10] * (1.0 - retrievability)).exp() - 1.0)
+                * hard_penalty
+                * easy_bonus
+                + 1.0)
+    }
+
+    fn forget_stability(&self, difficulty: f64, stability: f64, retrievability: f64) -> f64 {
+        let w = &self.weights;
+        let forgotten = w[11]
+            * difficulty.powf(-w[12])
+            * ((stability + 1.0).powf(w[13]) - 1.0)
+            * (w[14] * (1.0 - retrievability)).exp();
+        forgotten.min(stability)
+    }
+
+    fn interval_days(&self, stability: f64) -> f64 {
+        let interval = stability / Fsrs::FACTOR * (self.desired_retention.powf(1.0 / Fsrs::DECAY) - 1.0);
+        interval.round().clamp(1.0, self.maximum_interval_days)
+    }
+}
+
+impl Scheduler for Fsrs {
+    fn kind(&self) -> SchedulerKind {
+        SchedulerKind::Fsrs
+    }
+
+    fn review(&self, previous: Option<&CardState>, grade: Grade, now: Timestamp) -> CardState {
+        let value = Fsrs::grade_value(grade);
+        let memory = previous.and_then(|previous| match previous.memory {
+            Memory::Fsrs { stability, difficulty } => Some((previous, stability, difficulty)),
+            _ => None,
+        });
+        let (stability, difficulty) = match memory {
+            // The first four weights are the initial stability for each grade.
+            None => (self.weights[grade as usize], self.initial_difficulty(value)),
+            Some((previous, stability, difficulty)) => {
+                let retrievability = Fsrs::retrievability(previous.elapsed_days(now), stability);
+                let stability = if grade.is_correct() {
+                    self.recall_stability(difficulty, stability, retrievability, grade)
+                } else {
+                    self.forget_stability(difficulty, stability, retrievability)
+                };
+                (stability, self.next_difficulty(difficulty, value))
+            }
+        };
+        let memory = Memory::Fsrs { stability, difficulty };
+        CardState::after_review(previous, grade, now, memory, self.interval_days(stability))
+    }
+}
+
+/// A Leitner box system: a right answer moves the card up a box, a wrong one
+/// sends it back to the first, and each box is reviewed half as often as the
+/// one before it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Leitner;
+
+impl Leitner {
+    pub const LEVELS: u8 = 5;
+}
+
+impl Scheduler for Leitner {
+    fn kind(&self) -> SchedulerKind {
+        SchedulerKind::Leitner
+    }
+
+    fn review(&self, previous: Option<&CardState>, grade: Grade, now: Timestamp) -> CardState {
+        let level = match previous.map(|previous| previous.memory) {
+            Some(Memory::Leitner { level }) => level,
+            _ => 0,
+        };
+        let level = match grade {
+            Grade::Again => 1,
+            Grade::Hard => level.max(1),
+            Grade::Good => level.saturating_add(1),
+            Grade::Easy => level.saturating_add(2),
+        }
+        .min(Self::LEVELS);
+        // Box 1 is reviewed daily, box 2 every other day, then 4, 8 and 16.
+        let interval_days = f64::from(1u32 << (level - 1));
+        CardState::after_review(previous, grade, now, Memory::Leitner { level }, interval_days)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: Timestamp = 1_700_000_000_000.0;
+
+    fn interval_days(state: &CardState) -> f64 {
+        (state.due - state.last_reviewed) / DAY_MS
+    }
+
+    /// Reviews a card with each grade in turn, a day after it falls due.
+    fn review_all(scheduler: &dyn Scheduler, grades: &[Grade]) -> CardState {
+        let mut state: Option<CardState> = None;
+        for &grade in grades {
+            let now = state.map_or(NOW, |state| state.due + DAY_MS);
+            state = Some(scheduler.review(state.as_ref(), grade, now));
+        }
+        state.expect("at least one grade")
+    }
+
+    #[test]
+    fn sm2_grows_intervals_for_correct_answers() {
+        let first = Sm2.review(None, Grade::Good, NOW);
+        assert_eq!(interval_days(&first), 1.0);
+        assert_eq!(first.memory, Memory::Sm2 { ease: 2.5, interval_days: 1.0, repetitions: 1 });
+        let second = Sm2.review(Some(&first), Grade::Good, first.due);
+        assert_eq!(interval_days(&second), 6.0);
+        let third = Sm2.review(Some(&second), Grade::Good, second.due);
+        assert_eq!(interval_days(&third), 15.0);
+        assert_eq!(third.reviews, 3);
+        assert_eq!(third.lapses, 0);
+    }
+
+    #[test]
+    fn sm2_resets_forgotten_cards_and_keeps_a_minimum_ease() {
+        let learned = review_all(&Sm2, &[Grade::Good, Grade::Good, Grade::Good]);
+        let forgotten = Sm2.review(Some(&learned), Grade::Again, learned.due);
+        assert_eq!(interval_days(&forgotten), 1.0);
+        assert_eq!(forgotten.lapses, 1);
+        let Memory::Sm2 { repetitions, .. } = forgotten.memory else { panic!("not an SM-2 card") };
+        assert_eq!(repetitions, 0);
+
+        let struggling = review_all(&Sm2, &[Grade::Again; 10]);
+        let Memory::Sm2 { ease, .. } = struggling.memory else { panic!("not an SM-2 card") };
+        assert_eq!(ease, Sm2::MIN_EASE);
+    }
+
+    #[test]
+    fn fsrs_schedules_new_cards_from_the_initial_stability() {
+        let fsrs = Fsrs::default();
+        for (grade, stability) in Grade::ALL.into_iter().zip(fsrs.weights) {
+            let state = fsrs.review(None, grade, NOW);
+            let Memory::Fsrs { stability: found, difficulty } = state.memory else { panic!("not an FSRS card") };
+            assert_eq!(found, stability);
+            assert!((1.0..=10.0).contains(&difficulty));
+            // At 90% retention the interval equals the stability.
+            assert_eq!(interval_days(&state), stability.round().max(1.0));
+        }
+    }
+
+    #[test]
+    fn fsrs_stability_rises_on_recall_and_falls_on_lapse() {
+        let fsrs = Fsrs::default();
+        let stability = |state: &CardState| match state.memory {
+            Memory::Fsrs { stability, .. } => stability,
+            _ => panic!("not an FSRS card"),
+        };
+        let learned = review_all(&fsrs, &[Grade::Good]);
+        let recalled = fsrs.review(Some(&learned), Grade::Good, learned.due);
+        assert!(stability(&recalled) > stability(&learned));
+        assert!(interval_days(&recalled) > interval_days(&learned));
+
+        let forgotten = fsrs.review(Some(&recalled), Grade::Again, recalled.due);
+        assert!(stability(&forgotten) < stability(&recalled));
+        assert_eq!(forgotten.lapses, 1);
+    }
+
+    #[test]
+    fn fsrs_treats_cards_from_other_schedulers_as_new() {
+        let fsrs = Fsrs::default();
+        let sm2 = Sm2.review(None, Grade::Good, NOW);
+        let switched = fsrs.review(Some(&sm2), Grade::Good, sm2.due);
+        assert_eq!(switched.memory, fsrs.review(None, Grade::Good, sm2.due).memory);
+        assert_eq!(switched.reviews, 2);
+    }
+
+    #[test]
+    fn leitner_moves_cards_between_boxes() {
+        let level = |grades: &[Grade]| match review_all(&Leitner, grades).memory {
+            Memory::Leitner { level } => level,
+            _ => panic!("not a Leitner card"),
+        };
+        assert_eq!(level(&[Grade::Hard]), 1);
+        assert_eq!(level(&[Grade::Good]), 1);
+        assert_eq!(level(&[Grade::Good, Grade::Good]), 2);
+        assert_eq!(level(&[Grade::Good, Grade::Easy]), 3);
+        assert_eq!(level(&[Grade::Good, Grade::Easy, Grade::Hard]), 3);
+        assert_eq!(level(&[Grade::Good, Grade::Easy, Grade::Again]), 1);
+        assert_eq!(level(&[Grade::Easy, Grade::Easy, Grade::Easy, Grade::Good]), Leitner::LEVELS);
+    }
+
+    #[test]
+    fn leitner_doubles_the_interval_for_each_box() {
+        for level in 1..=Leitner::LEVELS {
+            let previous = CardState { due: NOW, last_reviewed: NOW, reviews: 1, lapses: 0, recalled: true, memory: Memory::Leitner { level } };
+            let state = Leitner.review(Some(&previous), Grade::Hard, NOW);
+            assert_eq!(interval_days(&state), f64::from(1u32 << (level - 1)));
+        }
+    }
+
+    #[test]
+    fn leitner_caps_out_of_range_boxes() {
+        let previous = CardState { due: NOW, last_reviewed: NOW, reviews: 1, lapses: 0, recalled: true, memory: Memory::Leitner { level: u8::MAX } };
+        for grade in [Grade::Good, Grade::Easy] {
+            let state = Leitner.review(Some(&previous), grade, NOW);
+            assert_eq!(state.memory, Memory::Leitner { level: Leitner::LEVELS });
+        }
+    }
+
+    #[test]
+    fn lapses_count_only_cards_forgotten_after_being_recalled() {
+        for kind in SchedulerKind::ALL {
+            let scheduler = kind.scheduler();
+            let never_learned = review_all(scheduler.as_ref(), &[Grade::Again, Grade::Again, Grade::Again]);
+            assert_eq!(never_learned.lapses, 0, "{}", kind);
+            assert!(!never_learned.recalled);
+            let relearned = review_all(scheduler.as_ref(), &[Grade::Good, Grade::Again, Grade::Again, Grade::Hard, Grade::Again]);
+            assert_eq!(relearned.lapses, 2, "{}", kind);
+            assert_eq!(relearned.reviews, 5);
+        }
+    }
+}
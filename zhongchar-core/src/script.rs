@@ -0,0 +1,175 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::character::Character;
+use crate::ids::UNKNOWN_COMPONENT;
+use crate::radical::{Radical, Simplification};
+
+/// Traditional ↔ simplified correspondences derived from the datasets.
+///
+/// Neither file lists them directly. The radicals' `Simplified` column gives
+/// a few dozen pairs outright, and the dictionary gives most of the rest:
+/// swapping a traditional radical for its simplified form in a character's
+/// decomposition (`⿰言吾` → `⿰讠吾`) leads to the simplified character (语)
+/// whenever that has its own entry. Irregular simplifications such as 鐵 → 铁
+/// are not covered.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptMap {
+    simplified: BTreeMap<char, char>,
+    traditional: BTreeMap<char, Vec<char>>,
+}
+
+impl ScriptMap {
+    pub fn build(radicals: &[Radical], dictionary: &[Character]) -> ScriptMap {
+        let definitions: HashMap<char, Option<&str>> = dictionary.iter()
+            .map(|character| (character.character, character.definition.as_deref()))
+            .collect();
+        let mut map = ScriptMap::default();
+        // Component substitutions, which include forms like 糹 → 纟 that only
+        // ever appear inside other characters.
+        let mut component_forms: HashMap<char, char> = HashMap::new();
+
+        for radical in radicals {
+            match &radical.simplified {
+                Some(Simplification::SimplifiesFrom(traditional)) => {
+                    for &form in traditional {
+                        map.insert(form, radical.radical_forms.primary);
+                    }
+                }
+                Some(Simplification::SimplifiedForm(simplified)) => {
+                    for form in radical.radical_forms.all().filter(|form| form != simplified) {
+                        component_forms.insert(form, *simplified);
+                    }
+                    // 肉 is written 月 as a component but is not simplified on
+                    // its own, so only pairs the dictionary defines alike
+                    // count as characters.
+                    let primary = radical.radical_forms.primary;
+                    let same_meaning = matches!(
+                        (definitions.get(&primary), definitions.get(simplified)),
+                        (Some(a), Some(b)) if a == b
+                    );
+                    if same_meaning {
+                        map.insert(primary, *simplified);
+                    }
+                }
+                None => {}
+            }
+        }
+
+        let mut by_decomposition: HashMap<&str, Vec<char>> = HashMap::new();
+        for character in dictionary {
+            by_decomposition.entry(&character.decomposition).or_default().push(character.character);
+        }
+        for character in dictionary {
+            let decomposition = &character.decomposition;
+            if decomposition.contains(UNKNOWN_COMPONENT) || !decomposition.chars().any(|c| component_forms.contains_key(&c)) {
+                continue;
+            }
+            let substituted: String = decomposition.chars()
+                .map(|c| component_forms.get(&c).copied().unwrap_or(c))
+                .collect();
+            // Some decompositions are too coarse to tell entries apart; skip
+            // those rather than guess.
+            if let Some([simplified]) = by_decomposition.get(substituted.as_str()).map(Vec::as_slice) {
+                map.insert(character.character, *simplified);
+            }
+        }
+        map
+    }
+
+    fn insert(&mut self, traditional: char, simplified: char) {
+        if traditional == simplified || self.simplified.contains_key(&traditional) {
+            return;
+        }
+        self.simplified.insert(traditional, simplified);
+        self.traditional.entry(simplified).or_default().push(traditional);
+    }
+
+    /// The simplified form of a traditional character, if it has a different one.
+    pub fn to_simplified(&self, traditional: char) -> Option<char> {
+        self.simplified.get(&traditional).copied()
+    }
+
+    /// The traditional characters a simplified one stands for. Usually one,
+    /// but mergers like 干 for 乾 and 幹 give several.
+    pub fn to_traditional(&self, simplified: char) -> &[char] {
+        self.traditional.get(&simplified).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Number of traditional characters with a known simplified form.
+    pub fn len(&self) -> usize {
+        self.simplified.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.simplified.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn radicals() -> Vec<Radical> {
+        Radical::parse_radicals(include_str!("../../public/radicals.csv")).unwrap()
+    }
+
+    fn shipped() -> ScriptMap {
+        let dictionary = Character::parse_dictionary(include_str!("../../public/dictionary.txt")).unwrap();
+        ScriptMap::build(&radicals(), &dictionary)
+    }
+
+    fn characters(lines: &[&str]) -> Vec<Character> {
+        Character::parse_dictionary(&lines.join("\n")).unwrap()
+    }
+
+    #[test]
+    fn maps_characters_through_their_simplified_components() {
+        let map = shipped();
+        assert_eq!(map.to_simplified('語'), Some('语'));
+        assert_eq!(map.to_traditional('语'), ['語']);
+        assert_eq!(map.to_simplified('紅'), Some('红'));
+        assert_eq!(map.to_simplified('语'), None);
+        assert!(map.to_traditional('語').is_empty());
+    }
+
+    #[test]
+    fn maps_radical_primary_forms_both_ways() {
+        let map = shipped();
+        // From the Simplified column: 言 → 讠, and 儿 simplifies 兒.
+        assert_eq!(map.to_simplified('言'), Some('讠'));
+        assert_eq!(map.to_traditional('讠'), ['言']);
+        assert_eq!(map.to_simplified('兒'), Some('儿'));
+        assert_eq!(map.to_traditional('儿'), ['兒']);
+        // 肉 is only written 月 as a component; the two mean different things.
+        assert_eq!(map.to_simplified('肉'), None);
+        assert!(!map.to_traditional('月').contains(&'肉'));
+    }
+
+    #[test]
+    fn skips_unknown_and_ambiguous_decompositions() {
+        let speech = radicals().into_iter().find(|radical| radical.number == 149).unwrap();
+        let dictionary = characters(&[
+            r#"{"character":"語","definition":"words","pinyin":["yǔ"],"decomposition":"⿰言吾","radical":"言","matches":[]}"#,
+            r#"{"character":"语","definition":"words","pinyin":["yǔ"],"decomposition":"⿰讠吾","radical":"讠","matches":[]}"#,
+            r#"{"character":"說","definition":"speak","pinyin":["shuō"],"decomposition":"⿰言兌","radical":"言","matches":[]}"#,
+            r#"{"character":"说","definition":"speak","pinyin":["shuō"],"decomposition":"⿰讠兌","radical":"讠","matches":[]}"#,
+            r#"{"character":"兑","definition":"speak","pinyin":["shuō"],"decomposition":"⿰讠兌","radical":"讠","matches":[]}"#,
+            r#"{"character":"訁","definition":"words","pinyin":["yán"],"decomposition":"⿰言？","radical":"言","matches":[]}"#,
+            r#"{"character":"讠","definition":"words","pinyin":["yán"],"decomposition":"⿰讠？","radical":"讠","matches":[]}"#,
+        ]);
+        let map = ScriptMap::build(&[speech], &dictionary);
+        assert_eq!(map.to_simplified('語'), Some('语'));
+        assert_eq!(map.to_simplified('說'), None);
+        assert_eq!(map.to_simplified('訁'), None);
+        // 言 and 讠 have no dictionary entries here, so they aren't paired.
+        assert_eq!(map.to_simplified('言'), None);
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn an_empty_dataset_gives_an_empty_map() {
+        let map = ScriptMap::build(&[], &[]);
+        assert!(map.is_empty());
+        assert_eq!(map.to_simplified('語'), None);
+    }
+}
@@ -0,0 +1,339 @@
+use super::character::Character;
+use super::pinyin::{parse_readings, Syllable};
+use super::radical::Radical;
+
+/// Latin letters carrying the diacritics used by pinyin, Vietnamese and
+/// Hepburn romaji, grouped by the plain letter they fold to.
+const DIACRITIC_FOLDS: &[(char, &str)] = &[
+    ('a', "àáâãäåāăąǎạảấầẩẫậắằẳẵặ"),
+    ('c', "çć"),
+    ('d', "đ"),
+    ('e', "èéêëēĕėęěẹẻẽếềểễệ"),
+    ('i', "ìíîïĩīĭįǐịỉ"),
+    ('n', "ñńňǹ"),
+    ('o', "òóôõöøōŏőǒơọỏốồổỗộớờởỡợ"),
+    ('u', "ùúûüũūŭůűųǔǖǘǚǜưụủứừửữự"),
+    ('y', "ýÿỳỵỷỹ"),
+];
+
+fn fold_char(c: char) -> char {
+    DIACRITIC_FOLDS.iter()
+        .find(|(_, variants)| variants.contains(c))
+        .map(|(base, _)| *base)
+        .unwrap_or(c)
+}
+
+/// Lowercases and strips diacritics so "shui" matches "shuǐ" and "nhat"
+/// matches "nhất".
+pub fn fold_diacritics(text: &str) -> String {
+    text.chars()
+        .flat_map(char::to_lowercase)
+        .map(fold_char)
+        .collect()
+}
+
+impl Radical {
+    /// Whether the query appears in any reading, meaning or glyph of the radical.
+    /// Pinyin also matches in tone-number form, so "shui3" finds 水.
+    pub fn matches_search(&self, query: &str) -> bool {
+        let query = fold_diacritics(query.trim());
+        if query.is_empty() {
+            return true;
+        }
+        if query.chars().any(|c| self.radical_forms.contains(c)) {
+            return true;
+        }
+        if query.ends_with(|c: char| c.is_ascii_digit()) {
+            let readings = parse_readings(&self.pinyin).unwrap_or_default();
+            if readings.iter().any(|syllable| syllable.to_ascii_numbered() == query) {
+                return true;
+            }
+        }
+        [
+            Some(&self.meaning),
+            self.colloquial_term.as_ref(),
+            Some(&self.pinyin),
+            Some(&self.han_viet),
+            Some(&self.hiragana_romaji.raw),
+            Some(&self.hangul_romaja.raw),
+        ]
+            .into_iter()
+            .flatten()
+            .any(|field| fold_diacritics(field).contains(&query))
+    }
+}
+
+impl Character {
+    /// Whether the query is the character itself, one of its readings (with
+    /// or without tones) or appears in its definition.
+    pub fn matches_search(&self, query: &str) -> bool {
+        let query = query.trim();
+        if query.is_empty() {
+            return true;
+        }
+        if query.chars().eq([self.character]) {
+            return true;
+        }
+        let folded_query = fold_diacritics(query);
+        let matches_reading = |reading: &String| {
+            let Ok(syllable) = Syllable::parse(reading) else { return false };
+            fold_diacritics(syllable.to_toneless()) == folded_query
+                || syllable.to_ascii_numbered() == folded_query
+        };
+        self.pinyin.iter().any(matches_reading)
+            || self.definition.as_deref().is_some_and(|definition| fold_diacritics(definition).contains(&folded_query))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortColumn {
+    Number,
+    StrokeCount,
+    Frequency,
+    Meaning,
+    Pinyin,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RadicalSort {
+    pub column: SortColumn,
+    pub direction: SortDirection,
+}
+
+impl Default for RadicalSort {
+    fn default() -> Self {
+        RadicalSort { column: SortColumn::Number, direction: SortDirection::Ascending }
+    }
+}
+
+impl RadicalSort {
+    /// Sorting by the column that's already active flips its direction,
+    /// any other column starts out ascending.
+    pub fn toggled(self, column: SortColumn) -> RadicalSort {
+        if self.column != column {
+            return RadicalSort { column, direction: SortDirection::Ascending };
+        }
+        let direction = match self.direction {
+            SortDirection::Ascending => SortDirection::Descending,
+            SortDirection::Descending => SortDirection::Ascending,
+        };
+        RadicalSort { column, direction }
+    }
+
+    /// Sorts by the chosen column, breaking ties by ascending radical number.
+    /// Pinyin is compared syllable by syllable with ü after u and by tone.
+    pub fn sort(&self, radicals: &mut [Radical]) {
+        radicals.sort_by(|a, b| {
+            let primary = match self.column {
+                SortColumn::Number => a.number.cmp(&b.number),
+                SortColumn::StrokeCount => a.stroke_count.cmp(&b.stroke_count),
+                SortColumn::Frequency => a.frequency.cmp(&b.frequency),
+                SortColumn::Meaning => a.meaning.to_lowercase().cmp(&b.meaning.to_lowercase()),
+                SortColumn::Pinyin => {
+                    let readings = |radical: &Radical| parse_readings(&radical.pinyin).unwrap_or_default();
+                    readings(a).cmp(&readings(b))
+                }
+            };
+            let primary = match self.direction {
+                SortDirection::Ascending => primary,
+                SortDirection::Descending => primary.reverse(),
+            };
+            primary.then(a.number.cmp(&b.number))
+        });
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RadicalFilter {
+    pub min_stroke_count: i32,
+    pub max_stroke_count: i32,
+    pub has_simplified: bool,
+    pub has_colloquial_term: bool,
+    pub min_frequency: i32,
+}
+
+impl Default for RadicalFilter {
+    fn default() -> Self {
+        RadicalFilter {
+            min_stroke_count: RadicalFilter::STROKE_COUNT_RANGE.0,
+            max_stroke_count: RadicalFilter::STROKE_COUNT_RANGE.1,
+            has_simplified: false,
+            has_colloquial_term: false,
+            min_frequency: 0,
+        }
+    }
+}
+
+impl RadicalFilter {
+    /// The Kangxi radicals run from one to seventeen strokes.
+    pub const STROKE_COUNT_RANGE: (i32, i32) = (1, 17);
+
+    pub fn matches(&self, radical: &Radical) -> bool {
+        (self.min_stroke_count..=self.max_stroke_count).contains(&radical.stroke_count)
+            && (!self.has_simplified || radical.simplified.is_some())
+            && (!self.has_colloquial_term || radical.colloquial_term.is_some())
+            && radical.frequency >= self.min_frequency
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn radicals() -> Vec<Radical> {
+        Radical::parse_radicals(include_str!("../../public/radicals.csv")).unwrap()
+    }
+
+    fn radical(number: i32) -> Radical {
+        radicals().into_iter().find(|radical| radical.number == number).unwrap()
+    }
+
+    #[test]
+    fn folds_diacritics_and_case() {
+        assert_eq!(fold_diacritics("Nhất Shuǐ LǛ"), "nhat shui lu");
+        assert_eq!(fold_diacritics("水 ōgai"), "水 ogai");
+    }
+
+    #[test]
+    fn finds_radicals_by_pinyin_with_or_without_tones() {
+        let water = radical(85);
+        // Tone marks are folded away, but a tone number has to be right.
+        for query in ["shuǐ", "shuī", "shui", "SHUI", " shui ", "shui3"] {
+            assert!(water.matches_search(query), "{}", query);
+        }
+        for query in ["shui2", "shui4"] {
+            assert!(!water.matches_search(query), "{}", query);
+        }
+    }
+
+    #[test]
+    fn finds_radicals_by_meaning_names_and_glyphs() {
+        let water = radical(85);
+        for query in ["", "water", "Wat", "三点水", "thuy", "mizu", "mulsu", "水", "氵"] {
+            assert!(water.matches_search(query), "{:?}", query);
+        }
+        for query in ["fire", "火", "ren"] {
+            assert!(!water.matches_search(query), "{}", query);
+        }
+    }
+
+    #[test]
+    fn finds_characters_by_whole_readings_and_definition() {
+        let line = r#"{"character":"好","definition":"good, excellent, fine; proper, suitable; well","pinyin":["hǎo"],"decomposition":"⿰女子","radical":"女","matches":[[0],[0],[0],[1],[1],[1]]}"#;
+        let good = Character::parse_dictionary(line).unwrap().remove(0);
+        for query in ["好", "hǎo", "hao", "hao3", "Good", "suitable"] {
+            assert!(good.matches_search(query), "{}", query);
+        }
+        // Readings must match a whole syllable; definitions may match a part.
+        for query in ["ha", "hao4", "女", "bad"] {
+            assert!(!good.matches_search(query), "{}", query);
+        }
+    }
+
+    fn sorted(column: SortColumn, direction: SortDirection) -> Vec<Radical> {
+        let mut radicals = radicals();
+        RadicalSort { column, direction }.sort(&mut radicals);
+        radicals
+    }
+
+    #[test]
+    fn toggling_flips_the_active_column_only() {
+        let sort = RadicalSort::default();
+        let by_strokes = sort.toggled(SortColumn::StrokeCount);
+        assert_eq!(by_strokes, RadicalSort { column: SortColumn::StrokeCount, direction: SortDirection::Ascending });
+        assert_eq!(by_strokes.toggled(SortColumn::StrokeCount).direction, SortDirection::Descending);
+        assert_eq!(by_strokes.toggled(SortColumn::StrokeCount).toggled(SortColumn::StrokeCount), by_strokes);
+        assert_eq!(sort.toggled(SortColumn::Number).direction, SortDirection::Descending);
+        let descending = RadicalSort { column: SortColumn::Meaning, direction: SortDirection::Descending };
+        assert_eq!(descending.toggled(SortColumn::Pinyin).direction, SortDirection::Ascending);
+    }
+
+    #[test]
+    fn sorts_numeric_columns_with_ties_in_number_order() {
+        for direction in [SortDirection::Ascending, SortDirection::Descending] {
+            for column in [SortColumn::StrokeCount, SortColumn::Frequency] {
+                let key = |radical: &Radical| match column {
+                    SortColumn::StrokeCount => radical.stroke_count,
+                    _ => radical.frequency,
+                };
+                let radicals = sorted(column, direction);
+                assert_eq!(radicals.len(), Radical::COUNT as usize);
+                for pair in radicals.windows(2) {
+                    let (a, b) = (key(&pair[0]), key(&pair[1]));
+                    let in_order = match direction {
+                        SortDirection::Ascending => a < b,
+                        SortDirection::Descending => a > b,
+                    };
+                    assert!(in_order || (a == b && pair[0].number < pair[1].number), "{:?} {:?}", column, direction);
+                }
+            }
+        }
+        let numbers = |radicals: Vec<Radical>| radicals.iter().map(|radical| radical.number).collect::<Vec<_>>();
+        assert_eq!(numbers(sorted(SortColumn::StrokeCount, SortDirection::Ascending))[..3], [1, 2, 3]);
+        assert_eq!(numbers(sorted(SortColumn::Number, SortDirection::Descending))[..2], [214, 213]);
+    }
+
+    #[test]
+    fn sorts_meanings_ignoring_case() {
+        let meanings = sorted(SortColumn::Meaning, SortDirection::Ascending).into_iter()
+            .map(|radical| radical.meaning.to_lowercase())
+            .collect::<Vec<_>>();
+        assert!(meanings.is_sorted());
+    }
+
+    #[test]
+    fn sorts_pinyin_by_syllable_then_tone() {
+        let mut radicals = radicals().into_iter()
+            .filter(|radical| [1, 4, 9, 10, 85].contains(&radical.number))
+            .collect::<Vec<_>>();
+        let order = |radicals: &[Radical]| radicals.iter().map(|radical| radical.number).collect::<Vec<_>>();
+        let mut sort = RadicalSort::default().toggled(SortColumn::Pinyin);
+        sort.sort(&mut radicals);
+        // ér, piě, rén, shuǐ, yī
+        assert_eq!(order(&radicals), [10, 4, 9, 85, 1]);
+        sort = sort.toggled(SortColumn::Pinyin);
+        sort.sort(&mut radicals);
+        assert_eq!(order(&radicals), [1, 85, 9, 4, 10]);
+    }
+
+    fn filtered(filter: RadicalFilter) -> Vec<i32> {
+        radicals().into_iter().filter(|radical| filter.matches(radical)).map(|radical| radical.number).collect()
+    }
+
+    #[test]
+    fn the_default_filter_keeps_every_radical() {
+        assert_eq!(filtered(RadicalFilter::default()).len(), Radical::COUNT as usize);
+    }
+
+    #[test]
+    fn filters_by_an_inclusive_stroke_range() {
+        let filter = RadicalFilter { min_stroke_count: 16, max_stroke_count: 17, ..RadicalFilter::default() };
+        assert_eq!(filtered(filter), [212, 213, 214]);
+        let filter = RadicalFilter { min_stroke_count: 5, max_stroke_count: 4, ..RadicalFilter::default() };
+        assert!(filtered(filter).is_empty());
+    }
+
+    #[test]
+    fn filters_by_simplified_colloquial_and_frequency() {
+        let water = radical(85);
+        let silk = radical(120);
+        let simplified = RadicalFilter { has_simplified: true, ..RadicalFilter::default() };
+        assert!(simplified.matches(&silk) && !simplified.matches(&water));
+        let colloquial = RadicalFilter { has_colloquial_term: true, ..RadicalFilter::default() };
+        assert!(colloquial.matches(&water) && !colloquial.matches(&radical(4)));
+        let frequent = RadicalFilter { min_frequency: 1595, ..RadicalFilter::default() };
+        assert!(frequent.matches(&water) && !frequent.matches(&silk));
+        let all = RadicalFilter { has_simplified: true, has_colloquial_term: true, min_frequency: 800, ..RadicalFilter::default() };
+        assert!(filtered(all).iter().all(|number| {
+            let radical = radical(*number);
+            radical.simplified.is_some() && radical.colloquial_term.is_some() && radical.frequency >= 800
+        }));
+        assert!(all.matches(&silk));
+    }
+}
@@ -0,0 +1,312 @@
+//! Grading hand-traced strokes against Make Me a Hanzi medians.
+
+use serde::{Deserialize, Serialize};
+
+use crate::graphics::CharacterGraphics;
+
+/// A point in graphics coordinates: a 1024-unit square, y pointing up.
+pub type Point = (f64, f64);
+
+/// Points both lines are resampled to before comparing them.
+const SAMPLES: usize = 16;
+/// How far, on average, a tracing may stray from the median and still count
+/// as the same stroke. Strokes themselves are around 100 units wide.
+const MAX_AVERAGE_DISTANCE: f64 = 200.0;
+/// How far the tracing may start or end from the median's ends.
+const MAX_END_DISTANCE: f64 = 250.0;
+/// Tracings whose length is outside these multiples of the median's length
+/// are a different shape, however close their points.
+const LENGTH_RATIO_RANGE: (f64, f64) = (0.4, 2.5);
+/// Anything shorter is a tap or slip and isn't graded.
+const MIN_TRACING_LENGTH: f64 = 16.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StrokeGrade {
+    Correct,
+    /// The right stroke, drawn from its end to its start.
+    WrongDirection,
+    /// A later stroke, drawn too early.
+    WrongOrder { stroke: usize },
+    WrongShape,
+}
+
+/// One pass at tracing a character, stroke by stroke.
+#[derive(Debug, Clone)]
+pub struct TracingQuiz {
+    medians: Vec<Vec<Point>>,
+    current: usize,
+    misses_on_current: u32,
+    right_first_time: usize,
+    mistakes: u32,
+}
+
+impl TracingQuiz {
+    /// Misses on one stroke before the learner is shown where it goes.
+    pub const HINT_AFTER_MISSES: u32 = 3;
+
+    pub fn new(graphics: &CharacterGraphics) -> TracingQuiz {
+        let medians = graphics.medians.iter()
+            .map(|median| median.iter().map(|&(x, y)| (x as f64, y as f64)).collect())
+            .collect();
+        TracingQuiz { medians, current: 0, misses_on_current: 0, right_first_time: 0, mistakes: 0 }
+    }
+
+    /// Grades a tracing against the next stroke, moving on if it's right.
+    /// Returns `None` once every stroke is done, or for a tracing too short
+    /// to be a deliberate stroke.
+    pub fn submit(&mut self, tracing: &[Point]) -> Option<StrokeGrade> {
+        let expected = self.medians.get(self.current)?;
+        if polyline_length(tracing) < MIN_TRACING_LENGTH {
+            return None;
+        }
+        let grade = match compare(tracing, expected) {
+            Fit::Forward => StrokeGrade::Correct,
+            Fit::Reversed => StrokeGrade::WrongDirection,
+            Fit::None => {
+                let later = self.medians.iter()
+                    .enumerate()
+                    .skip(self.current + 1)
+                    .find(|(_, median)| compare(tracing, median) != Fit::None);
+                match later {
+                    Some((stroke, _)) => StrokeGrade::WrongOrder { stroke },
+                    None => StrokeGrade::WrongShape,
+                }
+            }
+        };
+        if grade == StrokeGrade::Correct {
+            if self.misses_on_current == 0 {
+                self.right_first_time += 1;
+            }
+            self.current += 1;
+            self.misses_on_current = 0;
+        } else {
+            self.misses_on_current += 1;
+            self.mistakes += 1;
+        }
+        Some(grade)
+    }
+
+    /// The stroke to trace next, or `None` when the character is finished.
+    pub fn current_stroke(&self) -> Option<usize> {
+        (self.current < self.medians.len()).then_some(self.current)
+    }
+
+    pub fn hint_due(&self) -> bool {
+        self.misses_on_current >= Self::HINT_AFTER_MISSES
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.current >= self.medians.len()
+    }
+
+    pub fn stroke_count(&self) -> usize {
+        self.medians.len()
+    }
+
+    pub fn mistakes(&self) -> u32 {
+        self.mistakes
+    }
+
+    /// Share of strokes traced correctly at the first try.
+    pub fn accuracy(&self) -> f64 {
+        if self.medians.is_empty() {
+            return 1.0;
+        }
+        self.right_first_time as f64 / self.medians.len() as f64
+    }
+}
+
+/// Running tracing results for one character.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TracingStats {
+    pub attempts: u32,
+    pub total_accuracy: f64,
+    pub best_accuracy: f64,
+    pub last_accuracy: f64,
+}
+
+impl TracingStats {
+    pub fn record(&mut self, accuracy: f64) {
+        self.attempts += 1;
+        self.total_accuracy += accuracy;
+        self.best_accuracy = self.best_accuracy.max(accuracy);
+        self.last_accuracy = accuracy;
+    }
+
+    pub fn average_accuracy(&self) -> f64 {
+        if self.attempts == 0 {
+            return 0.0;
+        }
+        self.total_accuracy / self.attempts as f64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fit {
+    Forward,
+    Reversed,
+    None,
+}
+
+fn compare(tracing: &[Point], median: &[Point]) -> Fit {
+    // Nothing to trace against; `fits` needs the median's ends.
+    if median.is_empty() {
+        return Fit::None;
+    }
+    let median_length = polyline_length(median);
+    if median_length > 0.0 {
+        let ratio = polyline_length(tracing) / median_length;
+        if ratio < LENGTH_RATIO_RANGE.0 || ratio > LENGTH_RATIO_RANGE.1 {
+            return Fit::None;
+        }
+    }
+    let tracing = resample(tracing);
+    let mut median = resample(median);
+    if fits(&tracing, &median) {
+        return Fit::Forward;
+    }
+    median.reverse();
+    if fits(&tracing, &median) {
+        return Fit::Reversed;
+    }
+    Fit::None
+}
+
+fn fits(tracing: &[Point], median: &[Point]) -> bool {
+    let (Some(first), Some(last)) = (tracing.first(), tracing.last()) else { return false };
+    let ends_close = distance(*first, median[0]) <= MAX_END_DISTANCE
+        && distance(*last, median[median.len() - 1]) <= MAX_END_DISTANCE;
+    let average = tracing.iter()
+        .zip(median)
+        .map(|(a, b)| distance(*a, *b))
+        .sum::<f64>() / tracing.len() as f64;
+    ends_close && average <= MAX_AVERAGE_DISTANCE
+}
+
+fn distance(a: Point, b: Point) -> f64 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+fn polyline_length(points: &[Point]) -> f64 {
+    points.windows(2).map(|pair| distance(pair[0], pair[1])).sum()
+}
+
+/// `SAMPLES` points evenly spaced along the line, so lines drawn at
+/// different speeds or with different point counts line up.
+fn resample(points: &[Point]) -> Vec<Point> {
+    let Some(&first) = points.first() else { return Vec::new() };
+    let length = polyline_length(points);
+    if length == 0.0 {
+        return vec![first; SAMPLES];
+    }
+    let step = length / (SAMPLES - 1) as f64;
+    let mut samples = vec![first];
+    let mut travelled = 0.0;
+    let mut target = step;
+    for pair in points.windows(2) {
+        let segment = distance(pair[0], pair[1]);
+        while segment > 0.0 && travelled + segment >= target && samples.len() < SAMPLES {
+            let t = (target - travelled) / segment;
+            samples.push((pair[0].0 + (pair[1].0 - pair[0].0) * t, pair[0].1 + (pair[1].1 - pair[0].1) * t));
+            target += step;
+        }
+        travelled += segment;
+    }
+    // Rounding can leave the last sample short of the end.
+    while samples.len() < SAMPLES {
+        samples.push(points[points.len() - 1]);
+    }
+    samples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 十: a horizontal stroke, then a vertical one crossing it.
+    fn graphics(medians: Vec<Vec<(i32, i32)>>) -> CharacterGraphics {
+        CharacterGraphics { character: '十', strokes: vec![String::new(); medians.len()], medians }
+    }
+
+    fn shi() -> CharacterGraphics {
+        graphics(vec![vec![(100, 500), (900, 500)], vec![(500, 900), (500, 100)]])
+    }
+
+    #[test]
+    fn a_close_tracing_is_correct_and_moves_on() {
+        let mut quiz = TracingQuiz::new(&shi());
+        assert_eq!(quiz.submit(&[(120.0, 520.0), (500.0, 480.0), (880.0, 510.0)]), Some(StrokeGrade::Correct));
+        assert_eq!(quiz.current_stroke(), Some(1));
+        assert_eq!(quiz.submit(&[(490.0, 880.0), (510.0, 120.0)]), Some(StrokeGrade::Correct));
+        assert!(quiz.is_complete());
+        assert_eq!(quiz.current_stroke(), None);
+        assert_eq!(quiz.accuracy(), 1.0);
+        assert_eq!(quiz.submit(&[(100.0, 500.0), (900.0, 500.0)]), None);
+    }
+
+    #[test]
+    fn a_reversed_tracing_is_the_wrong_direction() {
+        let mut quiz = TracingQuiz::new(&shi());
+        assert_eq!(quiz.submit(&[(900.0, 500.0), (100.0, 500.0)]), Some(StrokeGrade::WrongDirection));
+        assert_eq!(quiz.current_stroke(), Some(0));
+        assert_eq!(quiz.mistakes(), 1);
+    }
+
+    #[test]
+    fn tracing_a_later_stroke_first_is_the_wrong_order() {
+        let mut quiz = TracingQuiz::new(&shi());
+        assert_eq!(quiz.submit(&[(500.0, 900.0), (500.0, 100.0)]), Some(StrokeGrade::WrongOrder { stroke: 1 }));
+        assert_eq!(quiz.current_stroke(), Some(0));
+    }
+
+    #[test]
+    fn a_tracing_matching_no_stroke_is_the_wrong_shape() {
+        let mut quiz = TracingQuiz::new(&shi());
+        assert_eq!(quiz.submit(&[(100.0, 100.0), (300.0, 300.0)]), Some(StrokeGrade::WrongShape));
+    }
+
+    #[test]
+    fn a_too_short_tracing_is_not_graded() {
+        let mut quiz = TracingQuiz::new(&shi());
+        assert_eq!(quiz.submit(&[(500.0, 500.0), (505.0, 505.0)]), None);
+        assert_eq!(quiz.submit(&[]), None);
+        assert_eq!(quiz.mistakes(), 0);
+        assert_eq!(quiz.current_stroke(), Some(0));
+    }
+
+    #[test]
+    fn an_empty_median_is_the_wrong_shape_rather_than_a_panic() {
+        assert_eq!(compare(&[(100.0, 500.0), (900.0, 500.0)], &[]), Fit::None);
+        let mut quiz = TracingQuiz::new(&graphics(vec![Vec::new()]));
+        assert_eq!(quiz.submit(&[(100.0, 500.0), (900.0, 500.0)]), Some(StrokeGrade::WrongShape));
+    }
+
+    #[test]
+    fn misses_bring_a_hint_and_lower_accuracy() {
+        let mut quiz = TracingQuiz::new(&shi());
+        for _ in 0..TracingQuiz::HINT_AFTER_MISSES {
+            assert!(!quiz.hint_due());
+            quiz.submit(&[(900.0, 500.0), (100.0, 500.0)]);
+        }
+        assert!(quiz.hint_due());
+        quiz.submit(&[(100.0, 500.0), (900.0, 500.0)]);
+        assert!(!quiz.hint_due());
+        quiz.submit(&[(500.0, 900.0), (500.0, 100.0)]);
+        assert!(quiz.is_complete());
+        assert_eq!(quiz.mistakes(), 3);
+        assert_eq!(quiz.accuracy(), 0.5);
+    }
+
+    #[test]
+    fn stats_keep_the_average_best_and_last_accuracy() {
+        let mut stats = TracingStats::default();
+        assert_eq!(stats.average_accuracy(), 0.0);
+        stats.record(0.5);
+        stats.record(1.0);
+        stats.record(0.75);
+        assert_eq!(stats.attempts, 3);
+        assert_eq!(stats.average_accuracy(), 0.75);
+        assert_eq!(stats.best_accuracy, 1.0);
+        assert_eq!(stats.last_accuracy, 0.75);
+    }
+}
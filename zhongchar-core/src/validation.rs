@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::character::Character;
+use crate::radical::{Radical, RadicalRow, Simplification};
+
+/// Every problem found in `radicals.csv`, in file order.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DatasetReport {
+    pub violations: Vec<Violation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    /// Line of `radicals.csv`, or `None` for problems with the file as a
+    /// whole such as a missing radical.
+    pub line: Option<u64>,
+    pub severity: Severity,
+    #[serde(flatten)]
+    pub kind: ViolationKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// The table itself is broken and the app would show wrong radicals.
+    Error,
+    /// The table disagrees with the dictionary. Both come from different
+    /// upstream sources, so some disagreement is expected.
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ViolationKind {
+    NumberOutOfRange { number: i32 },
+    DuplicateNumber { number: i32, first_line: u64 },
+    MissingNumber { number: i32 },
+    DuplicateForm { form: char, first_line: u64 },
+    StrokeCountMismatch { form: char, listed: i32, dictionary: usize },
+    ExampleNotInDictionary { example: char },
+    ExampleUnderOtherRadical { example: char, radical: char },
+}
+
+impl ViolationKind {
+    /// Checks against the dictionary are warnings: the table gives Kangxi
+    /// stroke counts where Make Me a Hanzi counts strokes as written today
+    /// (3 for 廴 against 2), its examples include rare variants the dictionary
+    /// lacks, and many are characters containing the radical rather than
+    /// characters indexed under it (王 for 一).
+    pub fn severity(&self) -> Severity {
+        match self {
+            ViolationKind::NumberOutOfRange { .. }
+            | ViolationKind::DuplicateNumber { .. }
+            | ViolationKind::MissingNumber { .. }
+            | ViolationKind::DuplicateForm { .. } => Severity::Error,
+            ViolationKind::StrokeCountMismatch { .. }
+            | ViolationKind::ExampleNotInDictionary { .. }
+            | ViolationKind::ExampleUnderOtherRadical { .. } => Severity::Warning,
+        }
+    }
+}
+
+impl fmt::Display for ViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ViolationKind::NumberOutOfRange { number } => {
+                write!(f, "radical number {} is outside 1–{}", number, Radical::COUNT)
+            }
+            ViolationKind::DuplicateNumber { number, first_line } => {
+                write!(f, "radical number {} already used on line {}", number, first_line)
+            }
+            ViolationKind::MissingNumber { number } => write!(f, "no row for radical number {}", number),
+            ViolationKind::DuplicateForm { form, first_line } => {
+                write!(f, "form {} already listed on line {}", form, first_line)
+            }
+            ViolationKind::StrokeCountMismatch { form, listed, dictionary } => {
+                write!(f, "{} is listed with {} strokes but the dictionary has {}", form, listed, dictionary)
+            }
+            ViolationKind::ExampleNotInDictionary { example } => {
+                write!(f, "example {} is not in the dictionary", example)
+            }
+            ViolationKind::ExampleUnderOtherRadical { example, radical } => {
+                write!(f, "example {} is filed under {} in the dictionary", example, radical)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}: {}", Radical::DATASET, line, self.severity, self.kind),
+            None => write!(f, "{}: {}: {}", Radical::DATASET, self.severity, self.kind),
+        }
+    }
+}
+
+impl DatasetReport {
+    /// Checks the radicals against themselves and against the dictionary:
+    /// numbers run 1–214 without gaps or repeats, no form belongs to two
+    /// rows, the primary form's stroke count matches the dictionary's stroke
+    /// data, and every example is a dictionary entry filed under the radical.
+    pub fn check(rows: &[RadicalRow], dictionary: &[Character]) -> DatasetReport {
+        let entries: HashMap<char, &Character> = dictionary.iter()
+            .map(|character| (character.character, character))
+            .collect();
+        let mut violations = Vec::new();
+        let mut push = |line: Option<u64>, kind: ViolationKind| {
+            violations.push(Violation { line, severity: kind.severity(), kind })
+        };
+        let mut number_lines: HashMap<i32, u64> = HashMap::new();
+        let mut form_lines: HashMap<char, u64> = HashMap::new();
+
+        for RadicalRow { line, radical } in rows {
+            let line = *line;
+            let number = radical.number;
+            if !(1..=Radical::COUNT).contains(&number) {
+                push(Some(line), ViolationKind::NumberOutOfRange { number });
+            } else if let Some(&first_line) = number_lines.get(&number) {
+                push(Some(line), ViolationKind::DuplicateNumber { number, first_line });
+            } else {
+                number_lines.insert(number, line);
+            }
+
+            for form in radical.radical_forms.all() {
+                match form_lines.get(&form) {
+                    Some(&first_line) => push(Some(line), ViolationKind::DuplicateForm { form, first_line }),
+                    None => {
+                        form_lines.insert(form, line);
+                    }
+                }
+            }
+
+            let primary = radical.radical_forms.primary;
+            if let Some(entry) = entries.get(&primary)
+                && entry.stroke_count() != radical.stroke_count as usize
+            {
+                push(Some(line), ViolationKind::StrokeCountMismatch {
+                    form: primary,
+                    listed: radical.stroke_count,
+                    dictionary: entry.stroke_count(),
+                });
+            }
+
+            // The dictionary files simplified characters under the simplified
+            // radical, e.g. 红 under 纟 rather than 糸.
+            let simplified_form = match radical.simplified {
+                Some(Simplification::SimplifiedForm(form)) => Some(form),
+                _ => None,
+            };
+            for &example in &radical.examples {
+                match entries.get(&example) {
+                    None => push(Some(line), ViolationKind::ExampleNotInDictionary { example }),
+                    Some(entry) if !radical.radical_forms.contains(entry.radical) && simplified_form != Some(entry.radical) => {
+                        push(Some(line), ViolationKind::ExampleUnderOtherRadical { example, radical: entry.radical });
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        for number in (1..=Radical::COUNT).filter(|number| !number_lines.contains_key(number)) {
+            push(None, ViolationKind::MissingNumber { number });
+        }
+        DatasetReport { violations }
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.violations.iter().filter(|violation| violation.severity == severity).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEADER: &str = "#\tRadical forms\tStroke count\tMeaning\tColloquial Term\tPīnyīn\tHán-Việt\tHiragana-Romaji\tHangul-Romaja\tFrequency\tSimplified\tExamples";
+
+    fn rows(rows: &[&str]) -> Vec<RadicalRow> {
+        let text = std::iter::once(HEADER).chain(rows.iter().copied()).collect::<Vec<_>>().join("\n");
+        Radical::parse_radical_rows(&text).unwrap()
+    }
+
+    fn dictionary() -> Vec<Character> {
+        Character::parse_dictionary(concat!(
+            r#"{"character":"一","pinyin":["yī"],"decomposition":"？","radical":"一","matches":[null]}"#, "\n",
+            r#"{"character":"丁","pinyin":["dīng"],"decomposition":"⿱一亅","radical":"一","matches":[[0],[1]]}"#, "\n",
+            r#"{"character":"王","pinyin":["wáng"],"decomposition":"？","radical":"王","matches":[null,null,null,null]}"#, "\n",
+            r#"{"character":"纟","pinyin":["sī"],"decomposition":"？","radical":"纟","matches":[null,null,null]}"#, "\n",
+            r#"{"character":"红","pinyin":["hóng"],"decomposition":"⿰纟工","radical":"纟","matches":[[0],[0],[0],[1],[1],[1]]}"#,
+        )).unwrap()
+    }
+
+    fn found(report: &DatasetReport) -> Vec<(Option<u64>, Severity, ViolationKind)> {
+        report.violations.iter()
+            .filter(|violation| !matches!(violation.kind, ViolationKind::MissingNumber { .. }))
+            .map(|violation| (violation.line, violation.severity, violation.kind.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn a_consistent_table_has_only_missing_numbers() {
+        let rows = rows(&[
+            "1\t一\t1\tone\t\tyī\tnhất\t\t\t42\t\t丁",
+            "120\t糸 (纟)\t6\tsilk\t\tmì\tmịch\t\t\t823\t纟\t红",
+        ]);
+        let report = DatasetReport::check(&rows, &dictionary());
+        assert_eq!(found(&report), []);
+        assert_eq!(report.violations.len(), Radical::COUNT as usize - 2);
+        assert_eq!(report.count(Severity::Error), report.violations.len());
+        assert_eq!(report.violations[0].kind, ViolationKind::MissingNumber { number: 2 });
+    }
+
+    #[test]
+    fn reports_each_kind_of_problem_on_its_line() {
+        let rows = rows(&[
+            "1\t一\t2\tone\t\tyī\tnhất\t\t\t42\t\t丁、王、丙",
+            "1\t丨\t1\tline\t\tgǔn\tcổn\t\t\t7\t\t",
+            "215\t一\t1\tone again\t\tyī\tnhất\t\t\t0\t\t",
+        ]);
+        let report = DatasetReport::check(&rows, &dictionary());
+        assert_eq!(found(&report), [
+            (Some(2), Severity::Warning, ViolationKind::StrokeCountMismatch { form: '一', listed: 2, dictionary: 1 }),
+            (Some(2), Severity::Warning, ViolationKind::ExampleUnderOtherRadical { example: '王', radical: '王' }),
+            (Some(2), Severity::Warning, ViolationKind::ExampleNotInDictionary { example: '丙' }),
+            (Some(3), Severity::Error, ViolationKind::DuplicateNumber { number: 1, first_line: 2 }),
+            (Some(4), Severity::Error, ViolationKind::NumberOutOfRange { number: 215 }),
+            (Some(4), Severity::Error, ViolationKind::DuplicateForm { form: '一', first_line: 2 }),
+        ]);
+        assert_eq!(report.violations[0].to_string(), "radicals.csv:2: warning: 一 is listed with 2 strokes but the dictionary has 1");
+        assert_eq!(report.violations.last().unwrap().to_string(), "radicals.csv: error: no row for radical number 214");
+    }
+
+    #[test]
+    fn the_shipped_table_has_no_errors() {
+        let rows = Radical::parse_radical_rows(include_str!("../../public/radicals.csv")).unwrap();
+        let dictionary = Character::parse_dictionary(include_str!("../../public/dictionary.txt")).unwrap();
+        let report = DatasetReport::check(&rows, &dictionary);
+        assert_eq!(report.count(Severity::Error), 0, "{:#?}", report.violations);
+        // Kangxi stroke counts that Make Me a Hanzi writes differently.
+        let stroke_counts = report.violations.iter()
+            .filter_map(|violation| match violation.kind {
+                ViolationKind::StrokeCountMismatch { form, .. } => Some(form),
+                _ => None,
+            })
+            .collect::<String>();
+        assert_eq!(stroke_counts, "廴瓦骨鬼鼎龜");
+    }
+}